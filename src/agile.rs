@@ -0,0 +1,178 @@
+//! Finding the cheapest times on half-hourly tariffs such as Agile.
+//!
+//! [`AgileRates`] takes unit rates from
+//! `list_electricity_tariff_standard_unit_rates` and answers three
+//! questions for a time window: which half-hours are cheapest, which
+//! contiguous block of a given length is cheapest, and when to start an
+//! appliance with a known energy profile. Prices can be negative. Rates are
+//! published a day ahead, so a window may only be partly covered; slots
+//! that have no rate are never chosen. Requires the `chrono` feature.
+//!
+//! ```no_run
+//! # async fn run(client: octopust::Client) -> Result<(), octopust::OctopustError> {
+//! use chrono::{Duration, Utc};
+//! use octopust::agile::AgileRates;
+//! use octopust::models::ListUnitRatesQuery;
+//!
+//! let rates = client
+//!     .list_electricity_tariff_standard_unit_rates(ListUnitRatesQuery {
+//!         product_code: "AGILE-24-10-01",
+//!         tariff_code: "E-1R-AGILE-24-10-01-C",
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//! let rates = AgileRates::new(&rates);
+//! let now = Utc::now();
+//! if let Some(block) = rates.cheapest_block(now, now + Duration::hours(24), 6) {
+//!     println!("run from {} to {}: {:.2}p/kWh", block.start, block.end, block.average_inc_vat());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::billing::Cost;
+use crate::models::TariffCharge;
+
+/// One half-hour and its unit rate, in pence per kWh.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slot {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub value_exc_vat: f64,
+    pub value_inc_vat: f64,
+}
+
+/// A run of consecutive slots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub slots: Vec<Slot>,
+    /// Cost in pence of the energy profile the block was chosen for. For
+    /// [`AgileRates::cheapest_block`] this is 1 kWh per slot.
+    pub cost: Cost,
+}
+
+impl Block {
+    /// Mean unit rate across the block, in pence per kWh including VAT.
+    pub fn average_inc_vat(&self) -> f64 {
+        self.slots.iter().map(|s| s.value_inc_vat).sum::<f64>() / self.slots.len() as f64
+    }
+
+    /// Mean unit rate across the block, in pence per kWh excluding VAT.
+    pub fn average_exc_vat(&self) -> f64 {
+        self.slots.iter().map(|s| s.value_exc_vat).sum::<f64>() / self.slots.len() as f64
+    }
+}
+
+/// Unit rates split into half-hour slots, in time order.
+#[derive(Debug, Clone, Default)]
+pub struct AgileRates {
+    slots: Vec<Slot>,
+}
+
+impl AgileRates {
+    /// Build from unit rates in any order.
+    ///
+    /// Rates longer than half an hour, as on Go or Cosy, are split into
+    /// half-hours. Open-ended rates (no `valid_to`) are skipped. Where
+    /// rates overlap, the first one given wins.
+    pub fn new(rates: impl AsRef<[TariffCharge]>) -> Self {
+        let half_hour = Duration::minutes(30);
+        let mut slots: Vec<Slot> = Vec::new();
+        for rate in rates.as_ref() {
+            let Some(valid_to) = rate.valid_to else {
+                continue;
+            };
+            let mut start = rate.valid_from.to_utc();
+            let valid_to = valid_to.to_utc();
+            while start < valid_to {
+                let end = (start + half_hour).min(valid_to);
+                slots.push(Slot {
+                    start,
+                    end,
+                    value_exc_vat: rate.value_exc_vat,
+                    value_inc_vat: rate.value_inc_vat,
+                });
+                start = end;
+            }
+        }
+        // Stable, so the first of several overlapping rates is kept.
+        slots.sort_by_key(|s| s.start);
+        slots.dedup_by_key(|s| s.start);
+        AgileRates { slots }
+    }
+
+    pub fn slots(&self) -> &[Slot] {
+        &self.slots
+    }
+
+    /// End of the last slot with a rate. Agile prices for the next day
+    /// usually appear around 4pm UK time.
+    pub fn available_until(&self) -> Option<DateTime<Utc>> {
+        self.slots.last().map(|s| s.end)
+    }
+
+    /// Slots lying wholly within `[from, to)`.
+    pub fn window(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> &[Slot] {
+        let first = self.slots.partition_point(|s| s.start < from);
+        let last = self.slots.partition_point(|s| s.end <= to).max(first);
+        &self.slots[first..last]
+    }
+
+    /// The `n` cheapest slots in the window, in time order. Returns fewer
+    /// if the window has fewer slots.
+    pub fn cheapest_slots(&self, from: DateTime<Utc>, to: DateTime<Utc>, n: usize) -> Vec<Slot> {
+        let mut slots = self.window(from, to).to_vec();
+        slots.sort_by(|a, b| a.value_inc_vat.total_cmp(&b.value_inc_vat).then(a.start.cmp(&b.start)));
+        slots.truncate(n);
+        slots.sort_by_key(|s| s.start);
+        slots
+    }
+
+    /// The cheapest run of `slots` consecutive half-hours in the window.
+    ///
+    /// `None` if the window holds no such run, e.g. because tomorrow's
+    /// prices are not out yet.
+    pub fn cheapest_block(&self, from: DateTime<Utc>, to: DateTime<Utc>, slots: usize) -> Option<Block> {
+        self.cheapest_start(from, to, &vec![1.0; slots])
+    }
+
+    /// The cheapest time to run an appliance using `profile[i]` kWh in its
+    /// `i`th half-hour. Negative prices make heavier slots more attractive,
+    /// so this can differ from [`cheapest_block`](Self::cheapest_block).
+    /// Ties go to the earliest start.
+    pub fn cheapest_start(&self, from: DateTime<Utc>, to: DateTime<Utc>, profile: &[f64]) -> Option<Block> {
+        if profile.is_empty() {
+            return None;
+        }
+        let window = self.window(from, to);
+        let mut best: Option<(usize, Cost)> = None;
+        for (i, run) in window.windows(profile.len()).enumerate() {
+            if run.windows(2).any(|pair| pair[0].end != pair[1].start) {
+                continue;
+            }
+            let cost = run.iter().zip(profile).fold(Cost::default(), |acc, (slot, kwh)| {
+                acc + Cost {
+                    exc_vat: kwh * slot.value_exc_vat,
+                    inc_vat: kwh * slot.value_inc_vat,
+                }
+            });
+            if best.is_none_or(|(_, best_cost)| cost.inc_vat < best_cost.inc_vat) {
+                best = Some((i, cost));
+            }
+        }
+
+        best.map(|(i, cost)| {
+            let slots = window[i..i + profile.len()].to_vec();
+            Block {
+                start: slots[0].start,
+                end: slots[slots.len() - 1].end,
+                slots,
+                cost,
+            }
+        })
+    }
+}
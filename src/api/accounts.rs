@@ -0,0 +1,15 @@
+use crate::api;
+use crate::error::OctopustError;
+use crate::http::Http;
+use crate::models::Account;
+
+pub async fn get_account(
+    http: &Http,
+    base_url: &str,
+    account_number: &str
+) -> Result<Account, OctopustError> {
+    http.require_api_key()?;
+    api::require_non_empty("account_number", account_number)?;
+    let url = format!("{}/accounts/{}/", base_url.trim_end_matches('/'), account_number);
+    api::get_json(http, &url, &[], "account").await
+}
@@ -1,53 +1,41 @@
-use reqwest::Client as HttpClient;
-use crate::error::{ApiError, OctopustError};
+use crate::api;
+use crate::error::OctopustError;
+use crate::http::Http;
 use crate::models::{ConsumptionResponse, ListElectrictyConsumptionQuery, ListGasConsumptionQuery, MpanInfo};
 
 pub async fn get_electricity_mpan(
-    http: &HttpClient,
+    http: &Http,
     base_url: &str,
     mpan: &str
 ) -> Result<MpanInfo, OctopustError> {
+    http.require_api_key()?;
+    api::require_non_empty("mpan", mpan)?;
     let url = format!("{}/electricity-meter-points/{}/", base_url.trim_end_matches('/'), mpan);
-    let resp = http.get(&url).send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
-
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!("API returned error status {status}: {body_str}"),
-        }));
-    }
-    
-    let mpan_info: MpanInfo = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse mpan info JSON: {e}. Response body: {body_str}",
-            ),
-        })
-    })?;
-    Ok(mpan_info)
+    api::get_json(http, &url, &[], "mpan info").await
 }
 
-pub async fn list_electricity_consumption(
-    http: &HttpClient,
-    base_url: &str,
-    query: ListElectrictyConsumptionQuery<'_>
-) -> Result<ConsumptionResponse, OctopustError> {
-    let url = format!("{}/electricity-meter-points/{}/meters/{}/consumption/", base_url.trim_end_matches('/'), query.mpan, query.serial_number);
+pub(crate) fn electricity_consumption_url(base_url: &str, query: &ListElectrictyConsumptionQuery<'_>) -> Result<String, OctopustError> {
+    api::require_non_empty("mpan", query.mpan)?;
+    api::require_non_empty("serial_number", query.serial_number)?;
+    Ok(format!(
+        "{}/electricity-meter-points/{}/meters/{}/consumption/",
+        base_url.trim_end_matches('/'),
+        query.mpan,
+        query.serial_number
+    ))
+}
 
+pub(crate) fn electricity_consumption_params(query: &ListElectrictyConsumptionQuery<'_>) -> Vec<(&'static str, String)> {
     // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
+    let mut params: Vec<(&'static str, String)> = Vec::new();
     if let Some(gb) = query.group_by {
         params.push(("group_by", gb.to_string()));
     }
     if let Some(pf) = query.period_from {
-        params.push(("period_from", pf.to_string()));
+        params.push(("period_from", api::format_timestamp(&pf)));
     }
     if let Some(pt) = query.period_to {
-        params.push(("period_to", pt.to_string()));
+        params.push(("period_to", api::format_timestamp(&pt)));
     }
     if let Some(order) = query.order_by {
         params.push(("order_by", order.to_string()));
@@ -58,48 +46,43 @@ pub async fn list_electricity_consumption(
     if let Some(ps) = query.page_size {
         params.push(("page_size", ps.to_string()));
     }
-
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
-
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!("API returned error status {status}: {body_str}"),
-        }));
-    }
-    
-    let consumption: ConsumptionResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse electricity consumption JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(consumption)
+    params
 }
 
-pub async fn list_gas_consumption(
-    http: &HttpClient,
+pub async fn list_electricity_consumption(
+    http: &Http,
     base_url: &str,
-    query: ListGasConsumptionQuery<'_>
+    query: ListElectrictyConsumptionQuery<'_>
 ) -> Result<ConsumptionResponse, OctopustError> {
-    let url = format!("{}/gas-meter-points/{}/meters/{}/consumption/", base_url.trim_end_matches('/'), query.mprn, query.serial_number);
-    
+    http.require_api_key()?;
+    let url = electricity_consumption_url(base_url, &query)?;
+    let params = electricity_consumption_params(&query);
+
+    api::get_json(http, &url, &params, "electricity consumption").await
+}
+
+pub(crate) fn gas_consumption_url(base_url: &str, query: &ListGasConsumptionQuery<'_>) -> Result<String, OctopustError> {
+    api::require_non_empty("mprn", query.mprn)?;
+    api::require_non_empty("serial_number", query.serial_number)?;
+    Ok(format!(
+        "{}/gas-meter-points/{}/meters/{}/consumption/",
+        base_url.trim_end_matches('/'),
+        query.mprn,
+        query.serial_number
+    ))
+}
+
+pub(crate) fn gas_consumption_params(query: &ListGasConsumptionQuery<'_>) -> Vec<(&'static str, String)> {
     // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
+    let mut params: Vec<(&'static str, String)> = Vec::new();
     if let Some(gb) = query.group_by {
         params.push(("group_by", gb.to_string()));
     }
     if let Some(pf) = query.period_from {
-        params.push(("period_from", pf.to_string()));
+        params.push(("period_from", api::format_timestamp(&pf)));
     }
     if let Some(pt) = query.period_to {
-        params.push(("period_to", pt.to_string()));
+        params.push(("period_to", api::format_timestamp(&pt)));
     }
     if let Some(order) = query.order_by {
         params.push(("order_by", order.to_string()));
@@ -110,27 +93,17 @@ pub async fn list_gas_consumption(
     if let Some(ps) = query.page_size {
         params.push(("page_size", ps.to_string()));
     }
+    params
+}
 
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
+pub async fn list_gas_consumption(
+    http: &Http,
+    base_url: &str,
+    query: ListGasConsumptionQuery<'_>
+) -> Result<ConsumptionResponse, OctopustError> {
+    http.require_api_key()?;
+    let url = gas_consumption_url(base_url, &query)?;
+    let params = gas_consumption_params(&query);
 
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!("API returned error status {status}: {body_str}"),
-        }));
-    }
-    
-    let consumption: ConsumptionResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse gas consumption JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(consumption)
+    api::get_json(http, &url, &params, "gas consumption").await
 }
\ No newline at end of file
@@ -1,43 +1,54 @@
-use reqwest::Client as HttpClient;
-use crate::error::{ApiError, OctopustError};
+use crate::api;
+use crate::error::OctopustError;
+use crate::http::Http;
+use std::collections::BTreeSet;
+
 use crate::models::{GridSupplyPointsResponse, ListGridSupplyPointsQuery};
+use crate::region::Region;
 
-pub async fn list_industry_grid_supply_points(
-    http: &HttpClient,
-    base_url: &str,
-    query: ListGridSupplyPointsQuery<'_>
-) -> Result<GridSupplyPointsResponse, OctopustError> {
-    let url = format!("{}/industry/grid-supply-points/", base_url.trim_end_matches('/'));
-    
+pub(crate) fn grid_supply_points_url(base_url: &str) -> String {
+    format!("{}/industry/grid-supply-points/", base_url.trim_end_matches('/'))
+}
+
+pub(crate) fn grid_supply_points_params(query: &ListGridSupplyPointsQuery<'_>) -> Vec<(&'static str, String)> {
     // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
+    let mut params: Vec<(&'static str, String)> = Vec::new();
     if let Some(p) = query.postcode {
         params.push(("postcode", p.to_string()));
     }
     if let Some(p) = query.page {
         params.push(("page", p.to_string()));
     }
+    params
+}
 
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
+pub async fn list_industry_grid_supply_points(
+    http: &Http,
+    base_url: &str,
+    query: ListGridSupplyPointsQuery<'_>
+) -> Result<GridSupplyPointsResponse, OctopustError> {
+    let url = grid_supply_points_url(base_url);
+    let params = grid_supply_points_params(&query);
+
+    api::get_json(http, &url, &params, "industry grid supply").await
+}
+
+pub async fn region_for_postcode(
+    http: &Http,
+    base_url: &str,
+    postcode: &str
+) -> Result<Region, OctopustError> {
+    api::require_non_empty("postcode", postcode)?;
+    let query = ListGridSupplyPointsQuery { postcode: Some(postcode), ..Default::default() };
+    let response = list_industry_grid_supply_points(http, base_url, query).await?;
 
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!("API returned error status {status}: {body_str}"),
-        }));
+    let regions: BTreeSet<Region> = response.results.iter().filter_map(|gsp| gsp.region()).collect();
+    match regions.len() {
+        0 => Err(OctopustError::InvalidInput(format!("no region found for postcode {postcode}"))),
+        1 => Ok(regions.into_iter().next().expect("one region")),
+        _ => Err(OctopustError::AmbiguousPostcode {
+            postcode: postcode.to_string(),
+            regions: regions.into_iter().collect(),
+        }),
     }
-    
-    let supply_points: GridSupplyPointsResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse industry grid supply JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(supply_points)
-}
\ No newline at end of file
+}
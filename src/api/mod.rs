@@ -1,4 +1,62 @@
 pub mod products;
 pub mod industry;
 pub mod consumption;
-pub mod tariffs;
\ No newline at end of file
+pub mod tariffs;
+pub mod accounts;
+
+use serde::de::DeserializeOwned;
+use crate::error::{DecodeError, OctopustError};
+use crate::http::Http;
+use crate::models::QueryTimestamp;
+
+/// Send a GET request and deserialize the JSON body.
+///
+/// Non-success statuses are classified by `OctopustError::from_response`;
+/// a body that does not match `T` becomes `OctopustError::Decode`.
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    http: &Http,
+    url: &str,
+    params: &[(&str, String)],
+    what: &str,
+) -> Result<T, OctopustError> {
+    let req = http.get(url).query(params);
+    let resp = http.send(req).await?;
+    let status = resp.status;
+    let body_bytes = resp.body;
+
+    if !status.is_success() {
+        let body_str = String::from_utf8_lossy(&body_bytes);
+        return Err(OctopustError::from_response(status, &resp.headers, &body_str));
+    }
+
+    let de = &mut serde_json::Deserializer::from_slice(&body_bytes);
+    serde_path_to_error::deserialize(de).map_err(|e| {
+        OctopustError::Decode(DecodeError {
+            status,
+            what: what.to_string(),
+            path: e.path().to_string(),
+            body: String::from_utf8_lossy(&body_bytes).into_owned(),
+            source: e.into_inner(),
+        })
+    })
+}
+
+/// Reject empty values for parameters that form part of the URL path.
+pub(crate) fn require_non_empty(name: &str, value: &str) -> Result<(), OctopustError> {
+    if value.trim().is_empty() {
+        return Err(OctopustError::InvalidInput(format!("{name} must not be empty")));
+    }
+    Ok(())
+}
+
+/// Render a query timestamp in the ISO 8601 form the API expects.
+#[cfg(feature = "chrono")]
+pub(crate) fn format_timestamp(timestamp: &QueryTimestamp<'_>) -> String {
+    timestamp.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Render a query timestamp in the ISO 8601 form the API expects.
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn format_timestamp(timestamp: &QueryTimestamp<'_>) -> String {
+    timestamp.to_string()
+}
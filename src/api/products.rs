@@ -1,18 +1,17 @@
-use reqwest::Client as HttpClient;
-use crate::error::{ApiError, OctopustError};
-use crate::models::{Product, ProductDetail, ProductQuery};
+use crate::api;
+use crate::error::OctopustError;
+use crate::http::Http;
+use crate::models::{Product, ProductDetail, ProductQuery, ProductsResponse, QueryTimestamp};
 
-pub async fn list_products(
-    http: &HttpClient,
-    base_url: &str,
-    query: ProductQuery<'_>
-) -> Result<Vec<Product>, OctopustError> {
-    let url = format!("{}/products/", base_url.trim_end_matches('/'));
-    
+pub(crate) fn list_products_url(base_url: &str) -> String {
+    format!("{}/products/", base_url.trim_end_matches('/'))
+}
+
+pub(crate) fn list_products_params(query: &ProductQuery<'_>) -> Vec<(&'static str, String)> {
     // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
+    let mut params: Vec<(&'static str, String)> = Vec::new();
     if let Some(aat) = query.available_at {
-        params.push(("available_at", aat.to_string()));
+        params.push(("available_at", api::format_timestamp(&aat)));
     }
     if let Some(br) = query.brand {
         params.push(("brand", br.to_string()));
@@ -35,82 +34,34 @@ pub async fn list_products(
     if let Some(p) = query.page {
         params.push(("page", p.to_string()));
     }
+    params
+}
 
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
-
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!("API returned error status {status}: {body_str}"),
-        }));
-    }
-
-    let json: serde_json::Value = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse products JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
+pub async fn list_products(
+    http: &Http,
+    base_url: &str,
+    query: ProductQuery<'_>
+) -> Result<Vec<Product>, OctopustError> {
+    let url = list_products_url(base_url);
+    let params = list_products_params(&query);
 
-    if let Some(arr) = json.get("results").and_then(|v| v.as_array()) {
-        let products: Vec<Product> = serde_json::from_value(arr.clone().into()).map_err(|e| {
-            OctopustError::Api(ApiError {
-                status,
-                message: format!(
-                    "Failed to parse products array: {e}. Response body: {body_str}"
-                ),
-            })
-        })?;
-        Ok(products)
-    } else {
-        Err(OctopustError::Api(ApiError {
-            status,
-            message: format!("Malformed products response: {body_str}"),
-        }))
-    }
+    let products: ProductsResponse = api::get_json(http, &url, &params, "products").await?;
+    Ok(products.results)
 }
 
 pub async fn retrieve_product(
-    http: &HttpClient,
+    http: &Http,
     base_url: &str,
     product_code: &str,
-    retrieve_product: Option<&str>,
+    tariffs_active_at: Option<QueryTimestamp<'_>>,
 ) -> Result<ProductDetail, OctopustError> {
+    api::require_non_empty("product_code", product_code)?;
     let url = format!("{}/products/{}/", base_url.trim_end_matches('/'), product_code);
     // Build query parameters only for values that are Some(...)
     let mut params: Vec<(&str, String)> = Vec::new();
-    if let Some(rp ) = retrieve_product {
-        params.push(("retrieve_product", rp.to_string()));
+    if let Some(ta) = tariffs_active_at {
+        params.push(("tariffs_active_at", api::format_timestamp(&ta)));
     }
 
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
-
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "API returned error status {status}: {body_str}"
-            ),
-        }));
-    }
-
-    let product_detail: ProductDetail = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse product JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(product_detail)
-}
\ No newline at end of file
+    api::get_json(http, &url, &params, "product").await
+}
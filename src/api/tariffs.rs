@@ -1,26 +1,34 @@
-use reqwest::Client as HttpClient;
-use crate::error::{ApiError, OctopustError};
+use crate::api;
+use crate::error::OctopustError;
+use crate::http::Http;
 use crate::models::{ListUnitRatesQuery, TariffChargesResponse};
 
-pub async fn list_electricity_tariff_day_unit_rates(
-    http: &HttpClient,
+pub(crate) fn tariff_charges_url(
     base_url: &str,
-    query: ListUnitRatesQuery<'_>
-) -> Result<TariffChargesResponse, OctopustError> {
-    let url = format!(
-        "{}/products/{}/electricity-tariffs/{}/day-unit-rates/",
+    fuel: &str,
+    charge: &str,
+    query: &ListUnitRatesQuery<'_>
+) -> Result<String, OctopustError> {
+    api::require_non_empty("product_code", query.product_code)?;
+    api::require_non_empty("tariff_code", query.tariff_code)?;
+    Ok(format!(
+        "{}/products/{}/{}-tariffs/{}/{}/",
         base_url.trim_end_matches('/'),
         query.product_code,
-        query.tariff_code
-    );
+        fuel,
+        query.tariff_code,
+        charge
+    ))
+}
 
+pub(crate) fn tariff_charges_params(query: &ListUnitRatesQuery<'_>) -> Vec<(&'static str, String)> {
     // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
+    let mut params: Vec<(&'static str, String)> = Vec::new();
     if let Some(pf) = query.period_from {
-        params.push(("period_from", pf.to_string()));
+        params.push(("period_from", api::format_timestamp(&pf)));
     }
     if let Some(pt) = query.period_to {
-        params.push(("period_to", pt.to_string()));
+        params.push(("period_to", api::format_timestamp(&pt)));
     }
     if let Some(p) = query.page {
         params.push(("page", p.to_string()));
@@ -28,294 +36,71 @@ pub async fn list_electricity_tariff_day_unit_rates(
     if let Some(ps) = query.page_size {
         params.push(("page_size", ps.to_string()));
     }
+    params
+}
 
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
-
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "API returned error status {status}: {body_str}"
-            ),
-        }));
-    }
+pub async fn list_electricity_tariff_day_unit_rates(
+    http: &Http,
+    base_url: &str,
+    query: ListUnitRatesQuery<'_>
+) -> Result<TariffChargesResponse, OctopustError> {
+    let url = tariff_charges_url(base_url, "electricity", "day-unit-rates", &query)?;
+    let params = tariff_charges_params(&query);
 
-    let day_unit_rate_response: TariffChargesResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse day unit rates JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(day_unit_rate_response)
+    api::get_json(http, &url, &params, "day unit rates").await
 }
 
 pub async fn list_electricity_tariff_night_unit_rates(
-    http: &HttpClient,
+    http: &Http,
     base_url: &str,
     query: ListUnitRatesQuery<'_>
 ) -> Result<TariffChargesResponse, OctopustError> {
-    let url = format!(
-        "{}/products/{}/electricity-tariffs/{}/night-unit-rates/",
-        base_url.trim_end_matches('/'),
-        query.product_code,
-        query.tariff_code
-    );
-
-    // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
-    if let Some(pf) = query.period_from {
-        params.push(("period_from", pf.to_string()));
-    }
-    if let Some(pt) = query.period_to {
-        params.push(("period_to", pt.to_string()));
-    }
-    if let Some(p) = query.page {
-        params.push(("page", p.to_string()));
-    }
-    if let Some(ps) = query.page_size {
-        params.push(("page_size", ps.to_string()));
-    }
-
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
-
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "API returned error status {status}: {body_str}"
-            ),
-        }));
-    }
+    let url = tariff_charges_url(base_url, "electricity", "night-unit-rates", &query)?;
+    let params = tariff_charges_params(&query);
 
-    let night_unit_rate_response: TariffChargesResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse night unit rates JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(night_unit_rate_response)
+    api::get_json(http, &url, &params, "night unit rates").await
 }
 
 pub async fn list_electricity_tariff_standard_unit_rates(
-    http: &HttpClient,
+    http: &Http,
     base_url: &str,
     query: ListUnitRatesQuery<'_>
 ) -> Result<TariffChargesResponse, OctopustError> {
-    let url = format!(
-        "{}/products/{}/electricity-tariffs/{}/standard-unit-rates/",
-        base_url.trim_end_matches('/'),
-        query.product_code,
-        query.tariff_code
-    );
-
-    // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
-    if let Some(pf) = query.period_from {
-        params.push(("period_from", pf.to_string()));
-    }
-    if let Some(pt) = query.period_to {
-        params.push(("period_to", pt.to_string()));
-    }
-    if let Some(p) = query.page {
-        params.push(("page", p.to_string()));
-    }
-    if let Some(ps) = query.page_size {
-        params.push(("page_size", ps.to_string()));
-    }
+    let url = tariff_charges_url(base_url, "electricity", "standard-unit-rates", &query)?;
+    let params = tariff_charges_params(&query);
 
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
-
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "API returned error status {status}: {body_str}"
-            ),
-        }));
-    }
-
-    let standard_unit_rate_response: TariffChargesResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse electricity tariff standard unit rates JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(standard_unit_rate_response)
+    api::get_json(http, &url, &params, "electricity tariff standard unit rates").await
 }
 
 pub async fn list_electricity_tariff_standing_charges(
-    http: &HttpClient,
+    http: &Http,
     base_url: &str,
     query: ListUnitRatesQuery<'_>
 ) -> Result<TariffChargesResponse, OctopustError> {
-    let url = format!(
-        "{}/products/{}/electricity-tariffs/{}/standing-charges/",
-        base_url.trim_end_matches('/'),
-        query.product_code,
-        query.tariff_code
-    );
-
-    // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
-    if let Some(pf) = query.period_from {
-        params.push(("period_from", pf.to_string()));
-    }
-    if let Some(pt) = query.period_to {
-        params.push(("period_to", pt.to_string()));
-    }
-    if let Some(p) = query.page {
-        params.push(("page", p.to_string()));
-    }
-    if let Some(ps) = query.page_size {
-        params.push(("page_size", ps.to_string()));
-    }
-
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
+    let url = tariff_charges_url(base_url, "electricity", "standing-charges", &query)?;
+    let params = tariff_charges_params(&query);
 
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "API returned error status {status}: {body_str}"
-            ),
-        }));
-    }
-
-    let standing_charges_response: TariffChargesResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse electricity standing charges JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(standing_charges_response)
+    api::get_json(http, &url, &params, "electricity standing charges").await
 }
 
 pub async fn list_gas_tariff_standard_unit_rates(
-    http: &HttpClient,
+    http: &Http,
     base_url: &str,
     query: ListUnitRatesQuery<'_>
 ) -> Result<TariffChargesResponse, OctopustError> {
-    let url = format!(
-        "{}/products/{}/gas-tariffs/{}/standard-unit-rates/",
-        base_url.trim_end_matches('/'),
-        query.product_code,
-        query.tariff_code
-    );
-
-    // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
-    if let Some(pf) = query.period_from {
-        params.push(("period_from", pf.to_string()));
-    }
-    if let Some(pt) = query.period_to {
-        params.push(("period_to", pt.to_string()));
-    }
-    if let Some(p) = query.page {
-        params.push(("page", p.to_string()));
-    }
-    if let Some(ps) = query.page_size {
-        params.push(("page_size", ps.to_string()));
-    }
-
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
-
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "API returned error status {status}: {body_str}"
-            ),
-        }));
-    }
+    let url = tariff_charges_url(base_url, "gas", "standard-unit-rates", &query)?;
+    let params = tariff_charges_params(&query);
 
-    let gas_unit_rate_response: TariffChargesResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse gas tariff standard unit rates JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(gas_unit_rate_response)
+    api::get_json(http, &url, &params, "gas tariff standard unit rates").await
 }
 
 pub async fn list_gas_tariff_standing_charges(
-    http: &HttpClient,
+    http: &Http,
     base_url: &str,
     query: ListUnitRatesQuery<'_>
 ) -> Result<TariffChargesResponse, OctopustError> {
-    let url = format!(
-        "{}/products/{}/gas-tariffs/{}/standing-charges/",
-        base_url.trim_end_matches('/'),
-        query.product_code,
-        query.tariff_code
-    );
-
-    // Build query parameters only for values that are Some(...)
-    let mut params: Vec<(&str, String)> = Vec::new();
-    if let Some(pf) = query.period_from {
-        params.push(("period_from", pf.to_string()));
-    }
-    if let Some(pt) = query.period_to {
-        params.push(("period_to", pt.to_string()));
-    }
-    if let Some(p) = query.page {
-        params.push(("page", p.to_string()));
-    }
-    if let Some(ps) = query.page_size {
-        params.push(("page_size", ps.to_string()));
-    }
-
-    let req = http.get(&url).query(&params);
-    let resp = req.send().await?;
-    let status = resp.status();
-    let body_bytes = resp.bytes().await?;
-    let body_str = String::from_utf8_lossy(&body_bytes);
-
-    if !status.is_success() {
-        return Err(OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "API returned error status {status}: {body_str}"
-            ),
-        }));
-    }
+    let url = tariff_charges_url(base_url, "gas", "standing-charges", &query)?;
+    let params = tariff_charges_params(&query);
 
-    let gas_standing_charges_response: TariffChargesResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
-        OctopustError::Api(ApiError {
-            status,
-            message: format!(
-                "Failed to parse gas tariff standing charges JSON: {e}. Response body: {body_str}"
-            ),
-        })
-    })?;
-    Ok(gas_standing_charges_response)
+    api::get_json(http, &url, &params, "gas tariff standing charges").await
 }
\ No newline at end of file
@@ -0,0 +1,295 @@
+//! Cost calculation from consumption and tariff charges.
+//!
+//! [`calculate_cost`] joins consumption readings with the unit rates and
+//! standing charges that were in force at the time, giving the cost of each
+//! interval, each day and the whole period, with and without VAT. All
+//! amounts are in pence. Requires the `chrono` feature.
+//!
+//! ```no_run
+//! # async fn run(client: octopust::Client) -> Result<(), octopust::OctopustError> {
+//! use octopust::billing::{calculate_cost, BillingOptions};
+//! use octopust::models::{ListElectrictyConsumptionQuery, ListUnitRatesQuery};
+//!
+//! let consumption = client
+//!     .list_electricity_consumption(ListElectrictyConsumptionQuery {
+//!         mpan: "2000000000000",
+//!         serial_number: "21L0000000",
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//! let tariff = ListUnitRatesQuery {
+//!     product_code: "AGILE-24-10-01",
+//!     tariff_code: "E-1R-AGILE-24-10-01-C",
+//!     ..Default::default()
+//! };
+//! let rates = client.list_electricity_tariff_standard_unit_rates(tariff).await?;
+//! let standing = client
+//!     .list_electricity_tariff_standing_charges(ListUnitRatesQuery {
+//!         product_code: "AGILE-24-10-01",
+//!         tariff_code: "E-1R-AGILE-24-10-01-C",
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//!
+//! let bill = calculate_cost(&consumption, &rates, &standing, &BillingOptions::default());
+//! println!("{:.2}p inc VAT", bill.total().inc_vat);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::ops::{Add, AddAssign};
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone};
+use chrono_tz::Europe::London;
+
+use crate::models::{ConsumptionReading, ConsumptionResponse, PaymentMethod, TariffCharge, TariffChargesResponse};
+
+/// kWh per cubic metre of gas, using the standard volume correction factor
+/// (1.02264) and a typical calorific value of 39.5 MJ/m³.
+///
+/// SMETS2 gas meters report consumption in m³; set
+/// [`BillingOptions::kwh_per_unit`] to this to price them.
+pub const GAS_KWH_PER_M3: f64 = 1.02264 * 39.5 / 3.6;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BillingOptions {
+    /// Charges for other payment methods are ignored. Charges without a
+    /// payment method always apply.
+    pub payment_method: PaymentMethod,
+    /// Multiplier from the consumption unit to kWh.
+    pub kwh_per_unit: f64,
+}
+
+impl Default for BillingOptions {
+    fn default() -> Self {
+        BillingOptions {
+            payment_method: PaymentMethod::DirectDebit,
+            kwh_per_unit: 1.0,
+        }
+    }
+}
+
+/// An amount in pence, with and without VAT.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Cost {
+    pub exc_vat: f64,
+    pub inc_vat: f64,
+}
+
+impl Add for Cost {
+    type Output = Cost;
+
+    fn add(self, other: Cost) -> Cost {
+        Cost {
+            exc_vat: self.exc_vat + other.exc_vat,
+            inc_vat: self.inc_vat + other.inc_vat,
+        }
+    }
+}
+
+impl AddAssign for Cost {
+    fn add_assign(&mut self, other: Cost) {
+        *self = *self + other;
+    }
+}
+
+/// The energy cost of one consumption reading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntervalCost {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+    /// Consumption in kWh.
+    pub consumption: f64,
+    pub cost: Cost,
+}
+
+/// Costs for one UK calendar day (Europe/London).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyCost {
+    pub date: NaiveDate,
+    /// Priced consumption in kWh.
+    pub consumption: f64,
+    pub energy: Cost,
+    pub standing_charge: Cost,
+}
+
+impl DailyCost {
+    pub fn total(&self) -> Cost {
+        self.energy + self.standing_charge
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CostBreakdown {
+    pub intervals: Vec<IntervalCost>,
+    pub days: Vec<DailyCost>,
+    /// Readings with no unit rate covering all of their interval. They are
+    /// left out of the totals.
+    pub unpriced: Vec<ConsumptionReading>,
+}
+
+impl CostBreakdown {
+    /// Priced consumption in kWh.
+    pub fn consumption(&self) -> f64 {
+        self.days.iter().map(|d| d.consumption).sum()
+    }
+
+    pub fn energy(&self) -> Cost {
+        self.days.iter().fold(Cost::default(), |acc, d| acc + d.energy)
+    }
+
+    pub fn standing_charge(&self) -> Cost {
+        self.days.iter().fold(Cost::default(), |acc, d| acc + d.standing_charge)
+    }
+
+    pub fn total(&self) -> Cost {
+        self.energy() + self.standing_charge()
+    }
+}
+
+impl AsRef<[ConsumptionReading]> for ConsumptionResponse {
+    fn as_ref(&self) -> &[ConsumptionReading] {
+        &self.results
+    }
+}
+
+impl AsRef<[TariffCharge]> for TariffChargesResponse {
+    fn as_ref(&self) -> &[TariffCharge] {
+        &self.results
+    }
+}
+
+/// Price `consumption` against `unit_rates` and `standing_charges`.
+///
+/// Each reading is matched to the unit rate whose `valid_from`/`valid_to`
+/// covers it. A reading that spans several rates, such as an hourly reading
+/// on Agile's half-hourly prices, is split between them in proportion to
+/// time. Where rates overlap, the one with the latest `valid_from` wins, so
+/// nothing is charged twice.
+///
+/// Days are UK calendar days (Europe/London), so the clock-change days are
+/// 23 and 25 hours long. Every day with at least one priced reading pays
+/// that day's standing charge; if the charge changes part-way through a
+/// day, each rate is charged for the fraction of the day it was in force.
+///
+/// Accepts the API responses directly, or slices collected from the
+/// `stream_*` methods.
+pub fn calculate_cost(
+    consumption: impl AsRef<[ConsumptionReading]>,
+    unit_rates: impl AsRef<[TariffCharge]>,
+    standing_charges: impl AsRef<[TariffCharge]>,
+    options: &BillingOptions,
+) -> CostBreakdown {
+    let applies = |c: &&TariffCharge| c.payment_method.is_none_or(|m| m == options.payment_method);
+    let unit_rates: Vec<&TariffCharge> = unit_rates.as_ref().iter().filter(applies).collect();
+    let standing_charges: Vec<&TariffCharge> = standing_charges.as_ref().iter().filter(applies).collect();
+
+    let mut breakdown = CostBreakdown::default();
+    let mut days: BTreeMap<NaiveDate, DailyCost> = BTreeMap::new();
+
+    for reading in consumption.as_ref() {
+        let (start, end) = (reading.interval_start, reading.interval_end);
+        let kwh = reading.consumption * options.kwh_per_unit;
+        let Some(cost) = price_interval(&unit_rates, start, end, kwh) else {
+            breakdown.unpriced.push(reading.clone());
+            continue;
+        };
+
+        let date = start.with_timezone(&London).date_naive();
+        let day = days.entry(date).or_insert_with(|| DailyCost {
+            date,
+            consumption: 0.0,
+            energy: Cost::default(),
+            standing_charge: daily_standing_charge(&standing_charges, date),
+        });
+        day.consumption += kwh;
+        day.energy += cost;
+        breakdown.intervals.push(IntervalCost {
+            start,
+            end,
+            consumption: kwh,
+            cost,
+        });
+    }
+
+    breakdown.days = days.into_values().collect();
+    breakdown
+}
+
+/// Split `[start, end)` into the spans covered by a charge, with the one
+/// charge that applies to each. Where charges overlap, the latest
+/// `valid_from` wins.
+fn in_force<'a>(
+    charges: &[&'a TariffCharge],
+    start: DateTime<FixedOffset>,
+    end: DateTime<FixedOffset>,
+) -> Vec<(Duration, &'a TariffCharge)> {
+    let mut bounds: Vec<DateTime<FixedOffset>> = vec![start, end];
+    for charge in charges {
+        bounds.push(charge.valid_from);
+        bounds.extend(charge.valid_to);
+    }
+    bounds.retain(|t| start <= *t && *t <= end);
+    bounds.sort();
+    bounds.dedup();
+
+    bounds
+        .windows(2)
+        .filter_map(|span| {
+            let charge = charges
+                .iter()
+                .filter(|c| c.valid_from <= span[0] && c.valid_to.is_none_or(|valid_to| span[0] < valid_to))
+                .max_by_key(|c| c.valid_from)?;
+            Some((span[1] - span[0], *charge))
+        })
+        .collect()
+}
+
+fn price_interval(
+    rates: &[&TariffCharge],
+    start: DateTime<FixedOffset>,
+    end: DateTime<FixedOffset>,
+    kwh: f64,
+) -> Option<Cost> {
+    let length = end - start;
+    if length <= Duration::zero() {
+        return None;
+    }
+
+    let mut covered = Duration::zero();
+    let mut cost = Cost::default();
+    for (span, rate) in in_force(rates, start, end) {
+        let share = kwh * fraction(span, length);
+        covered += span;
+        cost += Cost {
+            exc_vat: share * rate.value_exc_vat,
+            inc_vat: share * rate.value_inc_vat,
+        };
+    }
+    (covered >= length).then_some(cost)
+}
+
+/// Local midnight at the start of `date`.
+fn start_of_day(date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    London.from_local_datetime(&midnight).earliest().map(|t| t.fixed_offset())
+}
+
+fn daily_standing_charge(charges: &[&TariffCharge], date: NaiveDate) -> Cost {
+    let (Some(start), Some(end)) = (start_of_day(date), date.succ_opt().and_then(start_of_day)) else {
+        return Cost::default();
+    };
+
+    in_force(charges, start, end).into_iter().fold(Cost::default(), |acc, (span, charge)| {
+        let share = fraction(span, end - start);
+        acc + Cost {
+            exc_vat: share * charge.value_exc_vat,
+            inc_vat: share * charge.value_inc_vat,
+        }
+    })
+}
+
+fn fraction(part: Duration, whole: Duration) -> f64 {
+    part.num_seconds() as f64 / whole.num_seconds() as f64
+}
@@ -0,0 +1,49 @@
+//! The optional `config.toml` read at startup.
+
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// Settings that can be kept in a file instead of being passed on every
+/// invocation. Flags and environment variables take precedence.
+///
+/// ```toml
+/// api_key = "sk_live_..."
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    /// `octopust/config.toml` in the platform's config directory, e.g.
+    /// `~/.config/octopust/config.toml` on Linux.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("octopust").join("config.toml"))
+    }
+
+    /// Read the config from `path`, or from the default location if no path
+    /// was given. A missing file is only an error when it was asked for
+    /// explicitly.
+    pub fn load(path: Option<&Path>) -> Result<Config, Box<dyn Error>> {
+        let (path, explicit) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Config::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !explicit => return Ok(Config::default()),
+            Err(e) => return Err(format!("cannot read {}: {e}", path.display()).into()),
+        };
+        toml::from_str(&text).map_err(|e| format!("invalid config {}: {e}", path.display()).into())
+    }
+}
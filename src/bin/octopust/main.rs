@@ -0,0 +1,361 @@
+//! `octopust`: query the Octopus Energy API from the shell.
+//!
+//! Built with `cargo install octopust --features cli`. The API key is read
+//! from `--api-key`, then `OCTOPUS_API_KEY`, then `api_key` in the config
+//! file; products, rates and grid supply points work without one.
+
+mod config;
+mod output;
+
+use std::error::Error;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+use octopust::models::{
+    GroupBy, ListElectrictyConsumptionQuery, ListGasConsumptionQuery, ListGridSupplyPointsQuery,
+    ListUnitRatesQuery, Order, ProductQuery, RetrieveProductQuery,
+};
+use octopust::pagination::PaginationLimits;
+use octopust::tariff_code::Fuel;
+use octopust::{Client, TariffCode};
+
+use config::Config;
+use output::Format;
+
+#[derive(Debug, Parser)]
+#[command(name = "octopust", version, about = "Query the Octopus Energy API")]
+struct Cli {
+    /// API key for account and consumption endpoints.
+    #[arg(long, env = "OCTOPUS_API_KEY", hide_env_values = true, global = true)]
+    api_key: Option<String>,
+
+    /// Config file [default: octopust/config.toml in the user config directory].
+    #[arg(long, env = "OCTOPUST_CONFIG", global = true)]
+    config: Option<PathBuf>,
+
+    /// Base URL of the REST API.
+    #[arg(long, env = "OCTOPUS_BASE_URL", global = true)]
+    base_url: Option<String>,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Table, global = true)]
+    output: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List products.
+    Products(ProductsArgs),
+    /// Show a product with its tariffs in every region.
+    Product {
+        code: String,
+        /// Show the tariffs that were active at this time.
+        #[arg(long, value_parser = parse_time)]
+        tariffs_active_at: Option<DateTime<Utc>>,
+    },
+    /// List unit rates or standing charges for a tariff code.
+    Rates {
+        /// A full tariff code such as E-1R-AGILE-24-10-01-C.
+        tariff: TariffCode,
+        #[arg(long, value_enum, default_value_t = RateKind::Standard)]
+        kind: RateKind,
+        #[command(flatten)]
+        period: Period,
+    },
+    /// List half-hourly or grouped consumption for a meter.
+    Consumption {
+        #[arg(value_enum)]
+        fuel: FuelArg,
+        /// MPAN for electricity, MPRN for gas.
+        meter_point: String,
+        serial_number: String,
+        #[command(flatten)]
+        period: Period,
+        #[arg(long, value_enum)]
+        group_by: Option<GroupByArg>,
+        #[arg(long, value_enum)]
+        order: Option<OrderArg>,
+    },
+    /// List grid supply points, optionally for a postcode.
+    Gsp {
+        #[arg(long)]
+        postcode: Option<String>,
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Show the region a postcode belongs to.
+    Region { postcode: String },
+    /// Show an account with its properties, meter points and agreements.
+    Account { number: String },
+    /// Look up an electricity meter point's GSP and profile class.
+    MeterPoint { mpan: String },
+}
+
+#[derive(Debug, Args)]
+struct ProductsArgs {
+    #[arg(long)]
+    brand: Option<String>,
+    /// Products available at this time.
+    #[arg(long, value_parser = parse_time)]
+    available_at: Option<DateTime<Utc>>,
+    #[arg(long)]
+    business: Option<bool>,
+    #[arg(long)]
+    green: Option<bool>,
+    #[arg(long)]
+    historical: Option<bool>,
+    #[arg(long)]
+    tracker: Option<bool>,
+    #[arg(long)]
+    variable: Option<bool>,
+    /// Stop after this many products.
+    #[arg(long)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+struct Period {
+    /// Start of the period, as RFC 3339 or YYYY-MM-DD (UTC midnight).
+    #[arg(long, value_parser = parse_time)]
+    from: Option<DateTime<Utc>>,
+    /// End of the period, as RFC 3339 or YYYY-MM-DD (UTC midnight).
+    #[arg(long, value_parser = parse_time)]
+    to: Option<DateTime<Utc>>,
+    /// Stop after this many results.
+    #[arg(long)]
+    limit: Option<usize>,
+}
+
+impl Period {
+    fn limits(&self) -> PaginationLimits {
+        PaginationLimits { max_items: self.limit, ..Default::default() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum RateKind {
+    Standard,
+    Day,
+    Night,
+    Standing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FuelArg {
+    Electricity,
+    Gas,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum GroupByArg {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+}
+
+impl From<GroupByArg> for GroupBy {
+    fn from(arg: GroupByArg) -> Self {
+        match arg {
+            GroupByArg::Hour => GroupBy::Hour,
+            GroupByArg::Day => GroupBy::Day,
+            GroupByArg::Week => GroupBy::Week,
+            GroupByArg::Month => GroupBy::Month,
+            GroupByArg::Quarter => GroupBy::Quarter,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OrderArg {
+    /// Oldest first.
+    Asc,
+    /// Newest first.
+    Desc,
+}
+
+impl From<OrderArg> for Order {
+    fn from(arg: OrderArg) -> Self {
+        match arg {
+            OrderArg::Asc => Order::PeriodAsc,
+            OrderArg::Desc => Order::PeriodDesc,
+        }
+    }
+}
+
+/// A region as printed by the `region` subcommand.
+#[derive(Serialize)]
+struct RegionRow {
+    region: char,
+    gsp_group_id: String,
+    name: &'static str,
+}
+
+fn parse_time(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map(|date| date.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc())
+        .map_err(|_| format!("expected an RFC 3339 timestamp or YYYY-MM-DD, got {s:?}"))
+}
+
+fn client(cli: &Cli) -> Result<Client, Box<dyn Error>> {
+    let config = Config::load(cli.config.as_deref())?;
+    let mut builder = Client::builder();
+    if let Some(api_key) = cli.api_key.clone().or(config.api_key) {
+        builder = builder.api_key(api_key);
+    }
+    if let Some(base_url) = cli.base_url.clone().or(config.base_url) {
+        builder = builder.base_url(base_url);
+    }
+    Ok(builder.build()?)
+}
+
+async fn run(cli: Cli, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let client = client(&cli)?;
+    let format = cli.output;
+
+    match &cli.command {
+        Command::Products(args) => {
+            let query = ProductQuery {
+                available_at: args.available_at,
+                brand: args.brand.as_deref(),
+                is_business: args.business.as_ref(),
+                is_green: args.green.as_ref(),
+                is_historical: args.historical.as_ref(),
+                is_tracker: args.tracker.as_ref(),
+                is_variable: args.variable.as_ref(),
+                page: None,
+            };
+            let limits = PaginationLimits { max_items: args.limit, ..Default::default() };
+            output::write_stream(out, format, client.stream_products(query, limits)).await?;
+        }
+        Command::Product { code, tariffs_active_at } => {
+            let query = RetrieveProductQuery { product_code: code, tariffs_active_at: *tariffs_active_at };
+            let product = client.retrieve_product(query).await?;
+            output::write_one(out, format, &product)?;
+        }
+        Command::Rates { tariff, kind, period } => {
+            let query = ListUnitRatesQuery {
+                product_code: tariff.product_code(),
+                tariff_code: tariff.as_str(),
+                period_from: period.from,
+                period_to: period.to,
+                ..Default::default()
+            };
+            let limits = period.limits();
+            match (tariff.fuel(), kind) {
+                (Fuel::Electricity, RateKind::Standard) => {
+                    let rates = client.stream_electricity_tariff_standard_unit_rates(query, limits);
+                    output::write_stream(out, format, rates).await?;
+                }
+                (Fuel::Electricity, RateKind::Day) => {
+                    let rates = client.stream_electricity_tariff_day_unit_rates(query, limits);
+                    output::write_stream(out, format, rates).await?;
+                }
+                (Fuel::Electricity, RateKind::Night) => {
+                    let rates = client.stream_electricity_tariff_night_unit_rates(query, limits);
+                    output::write_stream(out, format, rates).await?;
+                }
+                (Fuel::Electricity, RateKind::Standing) => {
+                    let rates = client.stream_electricity_tariff_standing_charges(query, limits);
+                    output::write_stream(out, format, rates).await?;
+                }
+                (Fuel::Gas, RateKind::Standard) => {
+                    let rates = client.stream_gas_tariff_standard_unit_rates(query, limits);
+                    output::write_stream(out, format, rates).await?;
+                }
+                (Fuel::Gas, RateKind::Standing) => {
+                    let rates = client.stream_gas_tariff_standing_charges(query, limits);
+                    output::write_stream(out, format, rates).await?;
+                }
+                (Fuel::Gas, RateKind::Day | RateKind::Night) => {
+                    return Err(format!("gas tariff {tariff} has no day or night rates").into());
+                }
+            }
+        }
+        Command::Consumption { fuel, meter_point, serial_number, period, group_by, order } => {
+            let limits = period.limits();
+            match fuel {
+                FuelArg::Electricity => {
+                    let query = ListElectrictyConsumptionQuery {
+                        mpan: meter_point,
+                        serial_number,
+                        group_by: group_by.map(Into::into),
+                        period_from: period.from,
+                        period_to: period.to,
+                        order_by: order.map(Into::into),
+                        ..Default::default()
+                    };
+                    let readings = client.stream_electricity_consumption(query, limits);
+                    output::write_stream(out, format, readings).await?;
+                }
+                FuelArg::Gas => {
+                    let query = ListGasConsumptionQuery {
+                        mprn: meter_point,
+                        serial_number,
+                        group_by: group_by.map(Into::into),
+                        period_from: period.from,
+                        period_to: period.to,
+                        order_by: order.map(Into::into),
+                        ..Default::default()
+                    };
+                    let readings = client.stream_gas_consumption(query, limits);
+                    output::write_stream(out, format, readings).await?;
+                }
+            }
+        }
+        Command::Gsp { postcode, limit } => {
+            let query = ListGridSupplyPointsQuery { postcode: postcode.as_deref(), page: None };
+            let limits = PaginationLimits { max_items: *limit, ..Default::default() };
+            let points = client.stream_industry_grid_supply_points(query, limits);
+            output::write_stream(out, format, points).await?;
+        }
+        Command::Region { postcode } => {
+            let region = client.region_for_postcode(postcode).await?;
+            let row = RegionRow {
+                region: region.letter(),
+                gsp_group_id: region.gsp_group_id(),
+                name: region.name(),
+            };
+            output::write_one(out, format, &row)?;
+        }
+        Command::Account { number } => {
+            let account = client.get_account(number).await?;
+            output::write_one(out, format, &account)?;
+        }
+        Command::MeterPoint { mpan } => {
+            let meter_point = client.get_electricity_meter_point(mpan).await?;
+            output::write_one(out, format, &meter_point)?;
+        }
+    }
+    Ok(out.flush()?)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut out = BufWriter::new(io::stdout().lock());
+    match run(cli, &mut out).await {
+        Ok(()) => ExitCode::SUCCESS,
+        // Output piped into `head` and friends.
+        Err(e) if e.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) => {
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            drop(out);
+            eprintln!("octopust: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Rendering results as a table, JSON, NDJSON or CSV.
+
+use std::error::Error;
+use std::io::{self, Write};
+use std::pin::pin;
+use std::task::Poll;
+
+use clap::ValueEnum;
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Aligned columns for reading in a terminal.
+    Table,
+    /// A single pretty-printed JSON document.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+/// Write a single record, e.g. a product or an account.
+///
+/// JSON output is the object itself rather than a one-element array.
+pub fn write_one<T: Serialize>(out: &mut impl Write, format: Format, item: &T) -> io::Result<()> {
+    let value = serde_json::to_value(item)?;
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, &value)?;
+            writeln!(out)
+        }
+        _ => write_values(out, format, &[value]),
+    }
+}
+
+/// Write a list of records.
+pub fn write_many<T: Serialize>(out: &mut impl Write, format: Format, items: &[T]) -> io::Result<()> {
+    let values = items
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    write_values(out, format, &values)
+}
+
+/// Write records from a paginated stream.
+///
+/// NDJSON and CSV rows are written as they arrive, and the output is
+/// flushed whenever the next page has to be waited for. CSV columns come
+/// from the first record. Table and JSON output need every record first.
+pub async fn write_stream<T, E, S>(out: &mut impl Write, format: Format, stream: S) -> Result<(), Box<dyn Error>>
+where
+    T: Serialize,
+    E: Error + 'static,
+    S: Stream<Item = Result<T, E>>,
+{
+    let mut stream = pin!(stream);
+    match format {
+        Format::Table | Format::Json => {
+            let items: Vec<T> = stream.try_collect().await?;
+            write_many(out, format, &items)?;
+        }
+        Format::Ndjson => {
+            while let Some(item) = next_or_flush(&mut stream, || out.flush()).await? {
+                serde_json::to_writer(&mut *out, &item?)?;
+                writeln!(out)?;
+            }
+        }
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut *out);
+            let mut columns: Option<Vec<String>> = None;
+            while let Some(item) = next_or_flush(&mut stream, || writer.flush()).await? {
+                let value = serde_json::to_value(item?)?;
+                let columns = match &mut columns {
+                    Some(columns) => columns,
+                    None => {
+                        let first = self::columns(std::slice::from_ref(&value));
+                        writer.write_record(&first)?;
+                        columns.insert(first)
+                    }
+                };
+                writer.write_record(row(&value, columns))?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// The next item, flushing first if it is not ready yet.
+async fn next_or_flush<S: Stream + Unpin>(
+    stream: &mut S,
+    flush: impl FnOnce() -> io::Result<()>,
+) -> io::Result<Option<S::Item>> {
+    if let Poll::Ready(item) = futures::poll!(stream.next()) {
+        return Ok(item);
+    }
+    flush()?;
+    Ok(stream.next().await)
+}
+
+fn write_values(out: &mut impl Write, format: Format, values: &[Value]) -> io::Result<()> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, values)?;
+            writeln!(out)
+        }
+        Format::Ndjson => {
+            for value in values {
+                serde_json::to_writer(&mut *out, value)?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        Format::Table => write_table(out, values),
+        Format::Csv => write_csv(out, values),
+    }
+}
+
+/// Column names in the order they first appear. Records that are not JSON
+/// objects get a single `value` column.
+fn columns(values: &[Value]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for value in values {
+        match value {
+            Value::Object(map) => {
+                for key in map.keys() {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+            _ => {
+                if !columns.iter().any(|c| c == "value") {
+                    columns.push("value".to_string());
+                }
+            }
+        }
+    }
+    columns
+}
+
+fn row(value: &Value, columns: &[String]) -> Vec<String> {
+    columns
+        .iter()
+        .map(|column| match value {
+            Value::Object(map) => map.get(column).map(cell).unwrap_or_default(),
+            other if column == "value" => cell(other),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+/// Flatten a JSON value into a single cell; nested objects and arrays are
+/// kept as compact JSON.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn write_table(out: &mut impl Write, values: &[Value]) -> io::Result<()> {
+    let columns = columns(values);
+    if columns.is_empty() {
+        return Ok(());
+    }
+    let rows: Vec<Vec<String>> = values.iter().map(|v| row(v, &columns)).collect();
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut line = |cells: &[String]| -> io::Result<()> {
+        let last = cells.len() - 1;
+        for (i, (cell, width)) in cells.iter().zip(&widths).enumerate() {
+            if i == last {
+                writeln!(out, "{cell}")?;
+            } else {
+                write!(out, "{cell:<width$}  ")?;
+            }
+        }
+        Ok(())
+    };
+    line(&columns)?;
+    for row in &rows {
+        line(row)?;
+    }
+    Ok(())
+}
+
+fn write_csv(out: &mut impl Write, values: &[Value]) -> io::Result<()> {
+    let columns = columns(values);
+    if columns.is_empty() {
+        return Ok(());
+    }
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(&columns)?;
+    for value in values {
+        writer.write_record(row(value, &columns))?;
+    }
+    writer.flush()
+}
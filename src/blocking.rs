@@ -0,0 +1,366 @@
+//! A blocking client, for programs that don't run an async runtime.
+//!
+//! [`Client`] wraps the async [`crate::Client`] together with a private
+//! single-threaded tokio runtime, so it shares the same configuration,
+//! retries, rate limiting, models and [`OctopustError`]. `stream_*`
+//! methods become iterators. Requires the `blocking` feature.
+//!
+//! Do not call it from inside an async runtime: blocking on a future there
+//! panics. Use the async client instead.
+//!
+//! ```no_run
+//! use octopust::blocking::Client;
+//! use octopust::models::ProductQuery;
+//!
+//! let client = Client::new("API_KEY");
+//! let products = client.list_products(ProductQuery::default()).unwrap();
+//! println!("{} products", products.len());
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::runtime::Runtime;
+
+use crate::client::ClientBuilder;
+use crate::error::OctopustError;
+use crate::graphql::models::{AccountBalance, Bill, Dispatch, TelemetryReading};
+use crate::graphql::telemetry::{TelemetryPollOptions, TelemetryQuery};
+use crate::models::*;
+use crate::pagination::PaginationLimits;
+use crate::region::Region;
+
+/// Blocking counterpart of [`crate::Client`].
+#[derive(Clone)]
+pub struct Client {
+    inner: crate::Client,
+    kraken: Kraken,
+    runtime: Arc<Runtime>,
+}
+
+/// Blocking counterpart of [`crate::graphql::Kraken`].
+#[derive(Clone)]
+pub struct Kraken {
+    inner: crate::graphql::Kraken,
+    runtime: Arc<Runtime>,
+}
+
+/// Iterator over the items of a paginated stream.
+pub struct Iter<'a, T> {
+    stream: Pin<Box<dyn Stream<Item = Result<T, OctopustError>> + 'a>>,
+    runtime: &'a Runtime,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = Result<T, OctopustError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+impl ClientBuilder {
+    /// Build a blocking client.
+    pub fn build_blocking(self) -> Result<Client, OctopustError> {
+        let inner = self.build()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| OctopustError::Config(format!("failed to start runtime: {e}")))?;
+        let runtime = Arc::new(runtime);
+        Ok(Client {
+            kraken: Kraken {
+                inner: inner.kraken().clone(),
+                runtime: runtime.clone(),
+            },
+            inner,
+            runtime,
+        })
+    }
+}
+
+impl Client {
+    /// Create a new client with your API key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the HTTP client or runtime cannot be built, or if
+    /// `api_key` cannot be turned into a valid `Authorization` header. Use
+    /// [`Client::builder`] to handle these cases as errors.
+    pub fn new<S: Into<String>>(api_key: S) -> Self {
+        Client::builder()
+            .api_key(api_key)
+            .build_blocking()
+            .expect("failed to build octopust client")
+    }
+
+    /// Create a client that sends no API key.
+    pub fn anonymous() -> Self {
+        Client::builder()
+            .build_blocking()
+            .expect("failed to build octopust client")
+    }
+
+    /// Start configuring a client; finish with
+    /// [`ClientBuilder::build_blocking`].
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Whether this client sends an API key.
+    pub fn has_api_key(&self) -> bool {
+        self.inner.has_api_key()
+    }
+
+    /// Blocking handle for the Kraken GraphQL API.
+    pub fn kraken(&self) -> &Kraken {
+        &self.kraken
+    }
+
+    /// The base URL all endpoint paths are resolved against.
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    fn iter<'a, T>(&'a self, stream: impl Stream<Item = Result<T, OctopustError>> + 'a) -> Iter<'a, T> {
+        Iter {
+            stream: Box::pin(stream),
+            runtime: &self.runtime,
+        }
+    }
+
+    /// Get all products.
+    pub fn list_products(&self, query: ProductQuery<'_>) -> Result<Vec<Product>, OctopustError> {
+        self.block_on(self.inner.list_products(query))
+    }
+
+    /// Retrieve a specific product.
+    pub fn retrieve_product(&self, query: RetrieveProductQuery<'_>) -> Result<ProductDetail, OctopustError> {
+        self.block_on(self.inner.retrieve_product(query))
+    }
+
+    /// List electricity tariff day unit rates.
+    pub fn list_electricity_tariff_day_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+    ) -> Result<TariffChargesResponse, OctopustError> {
+        self.block_on(self.inner.list_electricity_tariff_day_unit_rates(query))
+    }
+
+    /// List electricity tariff night unit rates.
+    pub fn list_electricity_tariff_night_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+    ) -> Result<TariffChargesResponse, OctopustError> {
+        self.block_on(self.inner.list_electricity_tariff_night_unit_rates(query))
+    }
+
+    /// List electricity tariff standard unit rates.
+    pub fn list_electricity_tariff_standard_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+    ) -> Result<TariffChargesResponse, OctopustError> {
+        self.block_on(self.inner.list_electricity_tariff_standard_unit_rates(query))
+    }
+
+    /// List electricity tariff standing charges.
+    pub fn list_electricity_tariff_standing_charges(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+    ) -> Result<TariffChargesResponse, OctopustError> {
+        self.block_on(self.inner.list_electricity_tariff_standing_charges(query))
+    }
+
+    /// List gas tariff standard unit rates.
+    pub fn list_gas_tariff_standard_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+    ) -> Result<TariffChargesResponse, OctopustError> {
+        self.block_on(self.inner.list_gas_tariff_standard_unit_rates(query))
+    }
+
+    /// List gas tariff standing charges.
+    pub fn list_gas_tariff_standing_charges(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+    ) -> Result<TariffChargesResponse, OctopustError> {
+        self.block_on(self.inner.list_gas_tariff_standing_charges(query))
+    }
+
+    /// List electricity consumption, with optional query parameters.
+    pub fn list_electricity_consumption(
+        &self,
+        query: ListElectrictyConsumptionQuery<'_>,
+    ) -> Result<ConsumptionResponse, OctopustError> {
+        self.block_on(self.inner.list_electricity_consumption(query))
+    }
+
+    /// List gas consumption, with optional query parameters.
+    pub fn list_gas_consumption(&self, query: ListGasConsumptionQuery<'_>) -> Result<ConsumptionResponse, OctopustError> {
+        self.block_on(self.inner.list_gas_consumption(query))
+    }
+
+    /// Retrieve an account, with its properties, meter points and tariff agreements.
+    pub fn get_account(&self, account_number: &str) -> Result<Account, OctopustError> {
+        self.block_on(self.inner.get_account(account_number))
+    }
+
+    /// Look up an electricity meter point, including its GSP and profile class.
+    pub fn get_electricity_meter_point(&self, mpan: &str) -> Result<MpanInfo, OctopustError> {
+        self.block_on(self.inner.get_electricity_meter_point(mpan))
+    }
+
+    /// List grid supply points.
+    pub fn list_industry_grid_supply_points(
+        &self,
+        query: ListGridSupplyPointsQuery<'_>,
+    ) -> Result<GridSupplyPointsResponse, OctopustError> {
+        self.block_on(self.inner.list_industry_grid_supply_points(query))
+    }
+
+    /// The region a postcode belongs to; see [`crate::Client::region_for_postcode`].
+    pub fn region_for_postcode(&self, postcode: &str) -> Result<Region, OctopustError> {
+        self.block_on(self.inner.region_for_postcode(postcode))
+    }
+
+    /// Iterate over all products, following `next` links.
+    pub fn stream_products(&self, query: ProductQuery<'_>, limits: PaginationLimits) -> Iter<'_, Product> {
+        self.iter(self.inner.stream_products(query, limits))
+    }
+
+    /// Iterate over electricity tariff day unit rates across all pages.
+    pub fn stream_electricity_tariff_day_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits,
+    ) -> Iter<'_, TariffCharge> {
+        self.iter(self.inner.stream_electricity_tariff_day_unit_rates(query, limits))
+    }
+
+    /// Iterate over electricity tariff night unit rates across all pages.
+    pub fn stream_electricity_tariff_night_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits,
+    ) -> Iter<'_, TariffCharge> {
+        self.iter(self.inner.stream_electricity_tariff_night_unit_rates(query, limits))
+    }
+
+    /// Iterate over electricity tariff standard unit rates across all pages.
+    pub fn stream_electricity_tariff_standard_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits,
+    ) -> Iter<'_, TariffCharge> {
+        self.iter(self.inner.stream_electricity_tariff_standard_unit_rates(query, limits))
+    }
+
+    /// Iterate over electricity tariff standing charges across all pages.
+    pub fn stream_electricity_tariff_standing_charges(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits,
+    ) -> Iter<'_, TariffCharge> {
+        self.iter(self.inner.stream_electricity_tariff_standing_charges(query, limits))
+    }
+
+    /// Iterate over gas tariff standard unit rates across all pages.
+    pub fn stream_gas_tariff_standard_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits,
+    ) -> Iter<'_, TariffCharge> {
+        self.iter(self.inner.stream_gas_tariff_standard_unit_rates(query, limits))
+    }
+
+    /// Iterate over gas tariff standing charges across all pages.
+    pub fn stream_gas_tariff_standing_charges(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits,
+    ) -> Iter<'_, TariffCharge> {
+        self.iter(self.inner.stream_gas_tariff_standing_charges(query, limits))
+    }
+
+    /// Iterate over electricity consumption across all pages.
+    pub fn stream_electricity_consumption(
+        &self,
+        query: ListElectrictyConsumptionQuery<'_>,
+        limits: PaginationLimits,
+    ) -> Iter<'_, ConsumptionReading> {
+        self.iter(self.inner.stream_electricity_consumption(query, limits))
+    }
+
+    /// Iterate over gas consumption across all pages.
+    pub fn stream_gas_consumption(
+        &self,
+        query: ListGasConsumptionQuery<'_>,
+        limits: PaginationLimits,
+    ) -> Iter<'_, ConsumptionReading> {
+        self.iter(self.inner.stream_gas_consumption(query, limits))
+    }
+
+    /// Iterate over grid supply points across all pages.
+    pub fn stream_industry_grid_supply_points(
+        &self,
+        query: ListGridSupplyPointsQuery<'_>,
+        limits: PaginationLimits,
+    ) -> Iter<'_, GridSupplyPoint> {
+        self.iter(self.inner.stream_industry_grid_supply_points(query, limits))
+    }
+}
+
+impl Kraken {
+    /// The GraphQL endpoint this handle talks to.
+    pub fn url(&self) -> &str {
+        self.inner.url()
+    }
+
+    /// Run an authenticated query or mutation and deserialize its `data`.
+    pub fn execute<T: DeserializeOwned>(&self, document: &str, variables: Value) -> Result<T, OctopustError> {
+        self.runtime.block_on(self.inner.execute(document, variables))
+    }
+
+    /// Current balance of an account.
+    pub fn account_balance(&self, account_number: &str) -> Result<AccountBalance, OctopustError> {
+        self.runtime.block_on(self.inner.account_balance(account_number))
+    }
+
+    /// The most recent `first` bills on an account, newest first.
+    pub fn bills(&self, account_number: &str, first: u32) -> Result<Vec<Bill>, OctopustError> {
+        self.runtime.block_on(self.inner.bills(account_number, first))
+    }
+
+    /// Dispatches scheduled for the account's Intelligent device.
+    pub fn planned_dispatches(&self, account_number: &str) -> Result<Vec<Dispatch>, OctopustError> {
+        self.runtime.block_on(self.inner.planned_dispatches(account_number))
+    }
+
+    /// Dispatches that have already taken place.
+    pub fn completed_dispatches(&self, account_number: &str) -> Result<Vec<Dispatch>, OctopustError> {
+        self.runtime.block_on(self.inner.completed_dispatches(account_number))
+    }
+
+    /// Telemetry readings from a Home Mini for a time range.
+    pub fn smart_meter_telemetry(&self, query: TelemetryQuery<'_>) -> Result<Vec<TelemetryReading>, OctopustError> {
+        self.runtime.block_on(self.inner.smart_meter_telemetry(query))
+    }
+
+    /// Poll a Home Mini for new readings; see
+    /// [`crate::graphql::Kraken::poll_telemetry`]. The iterator ends after
+    /// yielding an error that is not retryable.
+    pub fn poll_telemetry(&self, device_id: &str, options: TelemetryPollOptions) -> Iter<'_, TelemetryReading> {
+        Iter {
+            stream: Box::pin(self.inner.poll_telemetry(device_id, options)),
+            runtime: &self.runtime,
+        }
+    }
+}
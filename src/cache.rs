@@ -0,0 +1,409 @@
+//! Response caching for REST GET requests.
+//!
+//! Enable with [`ClientBuilder::cache`](crate::ClientBuilder::cache). Responses
+//! are kept in an in-memory LRU and, optionally, a second-level
+//! [`CacheStore`] such as [`DiskStore`] that survives restarts. Each kind of
+//! endpoint has its own TTL; `None` disables caching for it. Once an entry
+//! is stale it is revalidated with `If-None-Match`/`If-Modified-Since` when
+//! the server sent an `ETag` or `Last-Modified`.
+//!
+//! Tariff charges for a range that ended in the past cannot change, so
+//! such responses never expire.
+//!
+//! Entries are keyed by URL and are not separated by API key, so don't
+//! share a [`DiskStore`] directory between users if consumption or
+//! account caching is turned on.
+
+use std::fmt;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use bytes::Bytes;
+use lru::LruCache;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::http::RawResponse;
+
+/// A cached response body and what is needed to revalidate it.
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub body: Bytes,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// When the entry goes stale; `None` if it never does.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl CacheEntry {
+    fn is_fresh(&self, now: SystemTime) -> bool {
+        self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
+
+/// Storage behind the in-memory cache.
+///
+/// Implementations should treat failures as misses; the cache never
+/// reports storage errors to callers.
+pub trait CacheStore: fmt::Debug + Send + Sync {
+    fn get(&self, key: &str) -> Option<CacheEntry>;
+    fn put(&self, key: &str, entry: &CacheEntry);
+}
+
+/// Cache settings. TTLs of `None` leave that kind of endpoint uncached.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// Number of responses kept in memory.
+    pub capacity: usize,
+    /// Store consulted when an entry is not in memory.
+    pub store: Option<Arc<dyn CacheStore>>,
+    /// `list_products` and `retrieve_product`.
+    pub products_ttl: Option<Duration>,
+    /// Unit rates and standing charges.
+    pub tariff_charges_ttl: Option<Duration>,
+    pub grid_supply_points_ttl: Option<Duration>,
+    pub meter_points_ttl: Option<Duration>,
+    pub consumption_ttl: Option<Duration>,
+    pub accounts_ttl: Option<Duration>,
+}
+
+impl Default for CacheConfig {
+    /// Public data is cached; consumption and account data is not. The
+    /// tariff charge TTL is short enough to pick up Agile's next-day
+    /// prices soon after they are published.
+    fn default() -> Self {
+        CacheConfig {
+            capacity: 1024,
+            store: None,
+            products_ttl: Some(Duration::from_secs(60 * 60)),
+            tariff_charges_ttl: Some(Duration::from_secs(15 * 60)),
+            grid_supply_points_ttl: Some(Duration::from_secs(24 * 60 * 60)),
+            meter_points_ttl: Some(Duration::from_secs(24 * 60 * 60)),
+            consumption_ttl: None,
+            accounts_ttl: None,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Add a [`DiskStore`] in `dir` behind the in-memory cache.
+    pub fn with_disk_store(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.store = Some(Arc::new(DiskStore::new(dir)));
+        self
+    }
+}
+
+/// How long a response may be served from the cache.
+enum Lifetime {
+    For(Duration),
+    Forever,
+}
+
+/// The in-memory LRU plus the optional second-level store.
+pub(crate) struct Cache {
+    config: CacheConfig,
+    memory: Mutex<LruCache<String, CacheEntry>>,
+}
+
+impl fmt::Debug for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache").field("config", &self.config).finish_non_exhaustive()
+    }
+}
+
+impl Cache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        let capacity = NonZeroUsize::new(config.capacity).unwrap_or(NonZeroUsize::MIN);
+        Cache {
+            memory: Mutex::new(LruCache::new(capacity)),
+            config,
+        }
+    }
+
+    /// Look `url` up, ignoring URLs whose endpoint is not cached.
+    pub(crate) fn lookup(&self, url: &Url) -> Option<Lookup> {
+        let lifetime = self.lifetime(url)?;
+        let key = url.as_str().to_string();
+        let entry = self.get(&key);
+        Some(Lookup { key, lifetime, entry })
+    }
+
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        if let Some(entry) = self.memory.lock().expect("cache lock poisoned").get(key) {
+            return Some(entry.clone());
+        }
+        let entry = self.config.store.as_ref()?.get(key)?;
+        self.memory.lock().expect("cache lock poisoned").put(key.to_string(), entry.clone());
+        Some(entry)
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) {
+        if let Some(store) = &self.config.store {
+            store.put(key, &entry);
+        }
+        self.memory.lock().expect("cache lock poisoned").put(key.to_string(), entry);
+    }
+
+    /// Match the URL path against the REST endpoints.
+    fn lifetime(&self, url: &Url) -> Option<Lifetime> {
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let position = |name: &str| segments.iter().position(|s| *s == name);
+
+        let ttl = if let Some(i) = position("products") {
+            if segments.len() == i + 5 && segments[i + 2].ends_with("-tariffs") {
+                if period_ended(url) {
+                    return Some(Lifetime::Forever);
+                }
+                self.config.tariff_charges_ttl
+            } else {
+                self.config.products_ttl
+            }
+        } else if position("grid-supply-points").is_some() {
+            self.config.grid_supply_points_ttl
+        } else if position("consumption").is_some() {
+            self.config.consumption_ttl
+        } else if position("electricity-meter-points").is_some() || position("gas-meter-points").is_some() {
+            self.config.meter_points_ttl
+        } else if position("accounts").is_some() {
+            self.config.accounts_ttl
+        } else {
+            None
+        };
+        ttl.map(Lifetime::For)
+    }
+}
+
+/// The state of one URL in the cache.
+pub(crate) struct Lookup {
+    key: String,
+    lifetime: Lifetime,
+    entry: Option<CacheEntry>,
+}
+
+impl Lookup {
+    /// A response that can be served without contacting the server.
+    pub(crate) fn fresh(&self) -> Option<RawResponse> {
+        self.entry
+            .as_ref()
+            .filter(|entry| entry.is_fresh(SystemTime::now()))
+            .map(|entry| cached_response(entry.body.clone()))
+    }
+
+    /// Validators to send when revalidating a stale entry.
+    pub(crate) fn conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let Some(entry) = &self.entry else {
+            return headers;
+        };
+        if let Some(value) = entry.etag.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(header::IF_NONE_MATCH, value);
+        }
+        if let Some(value) = entry.last_modified.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(header::IF_MODIFIED_SINCE, value);
+        }
+        headers
+    }
+
+    /// Store a fresh response, or turn a 304 into the cached response.
+    pub(crate) fn complete(self, cache: &Cache, resp: RawResponse) -> RawResponse {
+        let expires_at = match self.lifetime {
+            Lifetime::For(ttl) => Some(SystemTime::now() + ttl),
+            Lifetime::Forever => None,
+        };
+        match (resp.status, self.entry) {
+            (StatusCode::NOT_MODIFIED, Some(mut entry)) => {
+                entry.expires_at = expires_at;
+                let body = entry.body.clone();
+                cache.put(&self.key, entry);
+                cached_response(body)
+            }
+            (StatusCode::OK, _) => {
+                let header = |name| {
+                    resp.headers
+                        .get(name)
+                        .and_then(|v: &HeaderValue| v.to_str().ok())
+                        .map(str::to_string)
+                };
+                cache.put(
+                    &self.key,
+                    CacheEntry {
+                        body: resp.body.clone(),
+                        etag: header(header::ETAG),
+                        last_modified: header(header::LAST_MODIFIED),
+                        expires_at,
+                    },
+                );
+                resp
+            }
+            _ => resp,
+        }
+    }
+}
+
+fn cached_response(body: Bytes) -> RawResponse {
+    RawResponse {
+        status: StatusCode::OK,
+        headers: HeaderMap::new(),
+        body,
+    }
+}
+
+/// Whether the URL's `period_to` is in the past.
+fn period_ended(url: &Url) -> bool {
+    url.query_pairs()
+        .find(|(name, _)| name == "period_to")
+        .and_then(|(_, value)| parse_rfc3339(&value))
+        .is_some_and(|period_to| period_to < SystemTime::now())
+}
+
+/// Parse the ISO 8601 timestamps the API accepts, such as
+/// `2024-01-01T00:00:00Z` or `2024-01-01T00:00+01:00`.
+///
+/// Every field is range-checked, so dates such as `2023-02-29` are
+/// rejected rather than rolled over into the next month.
+pub(crate) fn parse_rfc3339(s: &str) -> Option<SystemTime> {
+    let (date, time) = s.split_once(['T', 't', ' '])?;
+    let [year, month, day] = numbers(date, '-', [4, 2, 2])?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    let (time, offset) = match time.strip_suffix(['Z', 'z']) {
+        Some(time) => (time, 0),
+        None => {
+            let (time, offset) = time.split_at(time.rfind(['+', '-'])?);
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let [hours, minutes] = match offset[1..].len() {
+                2 => [offset[1..].parse().ok()?, 0],
+                _ => numbers(&offset[1..], ':', [2, 2])?,
+            };
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            (time, sign * (hours * 3600 + minutes * 60))
+        }
+    };
+    // Fractional seconds are accepted and dropped.
+    let (time, fraction) = time.split_once('.').unwrap_or((time, "0"));
+    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let [hour, minute, second] = match time.len() {
+        5 => {
+            let [hour, minute] = numbers(time, ':', [2, 2])?;
+            [hour, minute, 0]
+        }
+        _ => numbers(time, ':', [2, 2, 2])?,
+    };
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    // Days since the epoch, from Howard Hinnant's `days_from_civil`.
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+}
+
+/// Split `s` on `separator` into fields of exactly `widths` digits.
+fn numbers<const N: usize>(s: &str, separator: char, widths: [usize; N]) -> Option<[i64; N]> {
+    let mut parts = s.split(separator);
+    let mut out = [0; N];
+    for (value, width) in out.iter_mut().zip(widths) {
+        let part = parts.next()?;
+        if part.len() != width || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *value = part.parse().ok()?;
+    }
+    parts.next().is_none().then_some(out)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A [`CacheStore`] keeping one file per response in a directory.
+#[derive(Debug, Clone)]
+pub struct DiskStore {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    body: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Seconds since the Unix epoch.
+    expires_at: Option<u64>,
+}
+
+impl DiskStore {
+    /// Store entries in `dir`, which is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        DiskStore { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        // FNV-1a, which unlike `DefaultHasher` is stable across releases.
+        let hash = key
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |h, b| (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3));
+        self.dir.join(format!("{hash:016x}.json"))
+    }
+}
+
+impl CacheStore for DiskStore {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        let data = fs::read(self.path(key)).ok()?;
+        let entry: DiskEntry = serde_json::from_slice(&data).ok()?;
+        if entry.key != key {
+            return None;
+        }
+        Some(CacheEntry {
+            body: general_purpose::STANDARD.decode(entry.body).ok()?.into(),
+            etag: entry.etag,
+            last_modified: entry.last_modified,
+            expires_at: entry.expires_at.map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+        })
+    }
+
+    fn put(&self, key: &str, entry: &CacheEntry) {
+        let disk_entry = DiskEntry {
+            key: key.to_string(),
+            body: general_purpose::STANDARD.encode(&entry.body),
+            etag: entry.etag.clone(),
+            last_modified: entry.last_modified.clone(),
+            expires_at: entry
+                .expires_at
+                .map(|t| t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())),
+        };
+        let Ok(data) = serde_json::to_vec(&disk_entry) else {
+            return;
+        };
+        // Write then rename, so readers never see a partial file.
+        let path = self.path(key);
+        let tmp = path.with_extension("tmp");
+        let _ = fs::create_dir_all(&self.dir)
+            .and_then(|()| fs::write(&tmp, data))
+            .and_then(|()| fs::rename(&tmp, &path));
+    }
+}
@@ -1,46 +1,77 @@
+use crate::cache::{Cache, CacheConfig};
 use crate::error::OctopustError;
+use crate::graphql::{Kraken, DEFAULT_GRAPHQL_URL};
+use crate::http::Http;
 use crate::models::*;
 use crate::api;
-use reqwest::{Client as HttpClient, header};
+use crate::pagination::{paginate, PaginationLimits};
+use crate::rate_limit::{RateLimit, RateLimiter};
+use crate::region::Region;
+use crate::retry::RetryPolicy;
+use futures::Stream;
+use reqwest::{Client as HttpClient, header, Proxy, Url};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 use base64::engine::general_purpose;
 use base64::Engine as _;
 
+const DEFAULT_BASE_URL: &str = "https://api.octopus.energy/v1/";
+
 /// Main API client for Octopus Energy.
 #[derive(Clone)]
 pub struct Client {
-    #[allow(dead_code)]
-    api_key: String,
-    http: Arc<HttpClient>,
+    http: Arc<Http>,
     base_url: String,
+    kraken: Kraken,
 }
 
 impl Client {
     /// Create a new client with your API key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the HTTP client cannot be built, or if `api_key` cannot be
+    /// turned into a valid `Authorization` header. Use [`Client::builder`]
+    /// to get either as an [`OctopustError::Config`] instead.
     pub fn new<S: Into<String>>(api_key: S) -> Self {
-        let api_key = api_key.into();
-        let mut headers = header::HeaderMap::new();
-        let auth = general_purpose::STANDARD.encode(format!("{api_key}:"));
-        headers.insert(
-            header::AUTHORIZATION,
-            header::HeaderValue::from_str(&format!("Basic {auth}" )).unwrap(),
-        );
-        headers.insert(
-            header::AUTHORIZATION,
-            header::HeaderValue::from_str(&format!("Basic {auth}")).unwrap(),
-        );
-        let http = HttpClient::builder()
-            .default_headers(headers)
+        Client::builder()
+            .api_key(api_key)
             .build()
-            .unwrap();
+            .expect("failed to build octopust client")
+    }
 
-        Client {
-            api_key: "".into(), // already embedded in headers
-            http: Arc::new(http),
-            base_url: "https://api.octopus.energy/v1/".to_string(),
-        }
+    /// Create a client that sends no API key.
+    ///
+    /// Products, tariff rates and grid supply points are public. Endpoints
+    /// that need a key, such as consumption, return
+    /// `OctopustError::MissingApiKey` without contacting the server.
+    pub fn anonymous() -> Self {
+        Client::builder()
+            .build()
+            .expect("failed to build octopust client")
     }
 
+    /// Start configuring a client.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Whether this client sends an API key.
+    pub fn has_api_key(&self) -> bool {
+        self.http.has_api_key()
+    }
+
+    /// Handle for the Kraken GraphQL API, sharing this client's HTTP
+    /// settings and API key.
+    pub fn kraken(&self) -> &Kraken {
+        &self.kraken
+    }
+
+    /// The base URL all endpoint paths are resolved against.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
 
     /// Get all products.
     pub async fn list_products(
@@ -164,6 +195,30 @@ impl Client {
         ).await
     }
 
+    /// Retrieve an account, with its properties, meter points and tariff agreements
+    pub async fn get_account(
+        &self,
+        account_number: &str
+    ) -> Result<Account, OctopustError> {
+        api::accounts::get_account(
+            &self.http,
+            &self.base_url,
+            account_number
+        ).await
+    }
+
+    /// Look up an electricity meter point, including its GSP and profile class
+    pub async fn get_electricity_meter_point(
+        &self,
+        mpan: &str
+    ) -> Result<MpanInfo, OctopustError> {
+        api::consumption::get_electricity_mpan(
+            &self.http,
+            &self.base_url,
+            mpan
+        ).await
+    }
+
     /// List grid supply points
     pub async fn list_industry_grid_supply_points(
         &self,
@@ -176,5 +231,385 @@ impl Client {
         ).await
     }
 
+    /// The region a postcode belongs to.
+    ///
+    /// Fails with `OctopustError::AmbiguousPostcode` if the postcode spans
+    /// several grid supply points.
+    pub async fn region_for_postcode(&self, postcode: &str) -> Result<Region, OctopustError> {
+        api::industry::region_for_postcode(&self.http, &self.base_url, postcode).await
+    }
+
+    /// Stream all products, following `next` links.
+    pub fn stream_products(
+        &self,
+        query: ProductQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<Product, OctopustError>> + '_ {
+        paginate::<ProductsResponse>(
+            &self.http,
+            Ok(api::products::list_products_url(&self.base_url)),
+            api::products::list_products_params(&query),
+            limits,
+            false,
+            "products",
+        )
+    }
+
+    /// Stream electricity tariff day unit rates across all pages.
+    pub fn stream_electricity_tariff_day_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<TariffCharge, OctopustError>> + '_ {
+        paginate::<TariffChargesResponse>(
+            &self.http,
+            api::tariffs::tariff_charges_url(&self.base_url, "electricity", "day-unit-rates", &query),
+            api::tariffs::tariff_charges_params(&query),
+            limits,
+            false,
+            "day unit rates",
+        )
+    }
+
+    /// Stream electricity tariff night unit rates across all pages.
+    pub fn stream_electricity_tariff_night_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<TariffCharge, OctopustError>> + '_ {
+        paginate::<TariffChargesResponse>(
+            &self.http,
+            api::tariffs::tariff_charges_url(&self.base_url, "electricity", "night-unit-rates", &query),
+            api::tariffs::tariff_charges_params(&query),
+            limits,
+            false,
+            "night unit rates",
+        )
+    }
+
+    /// Stream electricity tariff standard unit rates across all pages.
+    pub fn stream_electricity_tariff_standard_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<TariffCharge, OctopustError>> + '_ {
+        paginate::<TariffChargesResponse>(
+            &self.http,
+            api::tariffs::tariff_charges_url(&self.base_url, "electricity", "standard-unit-rates", &query),
+            api::tariffs::tariff_charges_params(&query),
+            limits,
+            false,
+            "electricity tariff standard unit rates",
+        )
+    }
+
+    /// Stream electricity tariff standing charges across all pages.
+    pub fn stream_electricity_tariff_standing_charges(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<TariffCharge, OctopustError>> + '_ {
+        paginate::<TariffChargesResponse>(
+            &self.http,
+            api::tariffs::tariff_charges_url(&self.base_url, "electricity", "standing-charges", &query),
+            api::tariffs::tariff_charges_params(&query),
+            limits,
+            false,
+            "electricity standing charges",
+        )
+    }
+
+    /// Stream gas tariff standard unit rates across all pages.
+    pub fn stream_gas_tariff_standard_unit_rates(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<TariffCharge, OctopustError>> + '_ {
+        paginate::<TariffChargesResponse>(
+            &self.http,
+            api::tariffs::tariff_charges_url(&self.base_url, "gas", "standard-unit-rates", &query),
+            api::tariffs::tariff_charges_params(&query),
+            limits,
+            false,
+            "gas tariff standard unit rates",
+        )
+    }
+
+    /// Stream gas tariff standing charges across all pages.
+    pub fn stream_gas_tariff_standing_charges(
+        &self,
+        query: ListUnitRatesQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<TariffCharge, OctopustError>> + '_ {
+        paginate::<TariffChargesResponse>(
+            &self.http,
+            api::tariffs::tariff_charges_url(&self.base_url, "gas", "standing-charges", &query),
+            api::tariffs::tariff_charges_params(&query),
+            limits,
+            false,
+            "gas tariff standing charges",
+        )
+    }
+
+    /// Stream electricity consumption across all pages.
+    pub fn stream_electricity_consumption(
+        &self,
+        query: ListElectrictyConsumptionQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<ConsumptionReading, OctopustError>> + '_ {
+        paginate::<ConsumptionResponse>(
+            &self.http,
+            api::consumption::electricity_consumption_url(&self.base_url, &query),
+            api::consumption::electricity_consumption_params(&query),
+            limits,
+            true,
+            "electricity consumption",
+        )
+    }
+
+    /// Stream gas consumption across all pages.
+    pub fn stream_gas_consumption(
+        &self,
+        query: ListGasConsumptionQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<ConsumptionReading, OctopustError>> + '_ {
+        paginate::<ConsumptionResponse>(
+            &self.http,
+            api::consumption::gas_consumption_url(&self.base_url, &query),
+            api::consumption::gas_consumption_params(&query),
+            limits,
+            true,
+            "gas consumption",
+        )
+    }
+
+    /// Stream grid supply points across all pages.
+    pub fn stream_industry_grid_supply_points(
+        &self,
+        query: ListGridSupplyPointsQuery<'_>,
+        limits: PaginationLimits
+    ) -> impl Stream<Item = Result<GridSupplyPoint, OctopustError>> + '_ {
+        paginate::<GridSupplyPointsResponse>(
+            &self.http,
+            Ok(api::industry::grid_supply_points_url(&self.base_url)),
+            api::industry::grid_supply_points_params(&query),
+            limits,
+            false,
+            "industry grid supply",
+        )
+    }
+
     // More endpoint methods would go here...
+}
+
+/// Builder for [`Client`].
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use octopust::Client;
+///
+/// let client = Client::builder()
+///     .api_key("API_KEY")
+///     .timeout(Duration::from_secs(30))
+///     .user_agent("my-app/1.0")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct ClientBuilder {
+    api_key: Option<String>,
+    base_url: Option<String>,
+    graphql_url: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxy: Option<Proxy>,
+    http_client: Option<HttpClient>,
+    retry: Option<RetryPolicy>,
+    rate_limit: Option<RateLimit>,
+    max_in_flight: Option<usize>,
+    cache: Option<CacheConfig>,
+}
+
+impl ClientBuilder {
+    /// Set the API key sent with every request.
+    ///
+    /// Without one the client is anonymous and can only call public endpoints.
+    pub fn api_key<S: Into<String>>(mut self, api_key: S) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Override the API base URL, e.g. to point at a local mock server.
+    pub fn base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Override the Kraken GraphQL endpoint, e.g. to point at a local
+    /// stand-in server.
+    pub fn graphql_url<S: Into<String>>(mut self, graphql_url: S) -> Self {
+        self.graphql_url = Some(graphql_url.into());
+        self
+    }
+
+    /// Total timeout for each request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for establishing a connection.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Value of the `User-Agent` header.
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Route requests through a proxy.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Use an existing `reqwest::Client` instead of building one.
+    ///
+    /// This cannot be combined with `timeout`, `connect_timeout`,
+    /// `user_agent` or `proxy`; configure those on the supplied client.
+    pub fn http_client(mut self, client: HttpClient) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// How transient failures are retried. Defaults to
+    /// `RetryPolicy::default()`; use `RetryPolicy::disabled()` to turn
+    /// retries off.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Limit how often requests are sent. The limit is shared by every
+    /// clone of the built client.
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// Cap the number of requests in flight at once across every clone of
+    /// the built client.
+    pub fn max_in_flight(mut self, max: usize) -> Self {
+        self.max_in_flight = Some(max);
+        self
+    }
+
+    /// Cache GET responses; see [`crate::cache`].
+    pub fn cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(config);
+        self
+    }
+
+    /// Build the client.
+    pub fn build(self) -> Result<Client, OctopustError> {
+        let base_url = self.base_url.unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        Url::parse(&base_url).map_err(|e| {
+            OctopustError::Config(format!("invalid base URL {base_url:?}: {e}"))
+        })?;
+        let graphql_url = self.graphql_url.unwrap_or_else(|| DEFAULT_GRAPHQL_URL.to_string());
+        Url::parse(&graphql_url).map_err(|e| {
+            OctopustError::Config(format!("invalid GraphQL URL {graphql_url:?}: {e}"))
+        })?;
+
+        let limiter = match self.rate_limit {
+            Some(limit) if limit.requests == 0 || limit.per.is_zero() => {
+                return Err(OctopustError::Config(
+                    "rate limit must allow at least one request per non-zero period".to_string(),
+                ));
+            }
+            Some(limit) => Some(RateLimiter::new(limit)),
+            None => None,
+        };
+        let in_flight = match self.max_in_flight {
+            Some(0) => {
+                return Err(OctopustError::Config(
+                    "max_in_flight must be at least 1".to_string(),
+                ));
+            }
+            Some(max) => Some(Semaphore::new(max)),
+            None => None,
+        };
+
+        let cache = match self.cache {
+            Some(config) if config.capacity == 0 => {
+                return Err(OctopustError::Config(
+                    "cache capacity must be at least 1".to_string(),
+                ));
+            }
+            Some(config) => Some(Cache::new(config)),
+            None => None,
+        };
+
+        let auth = match &self.api_key {
+            Some(api_key) => {
+                let encoded = general_purpose::STANDARD.encode(format!("{api_key}:"));
+                let mut value = header::HeaderValue::from_str(&format!("Basic {encoded}"))
+                    .map_err(|e| OctopustError::Config(format!("invalid API key: {e}")))?;
+                value.set_sensitive(true);
+                Some(value)
+            }
+            None => None,
+        };
+
+        let http = match self.http_client {
+            Some(client) => {
+                if self.timeout.is_some()
+                    || self.connect_timeout.is_some()
+                    || self.user_agent.is_some()
+                    || self.proxy.is_some()
+                {
+                    return Err(OctopustError::Config(
+                        "timeout, connect_timeout, user_agent and proxy must be set on the \
+                         supplied reqwest::Client"
+                            .to_string(),
+                    ));
+                }
+                client
+            }
+            None => {
+                let mut builder = HttpClient::builder();
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+
+        let http = Arc::new(Http::new(
+            http,
+            auth,
+            self.retry.unwrap_or_default(),
+            limiter,
+            in_flight,
+            cache,
+        ));
+        Ok(Client {
+            kraken: Kraken::new(http.clone(), graphql_url, self.api_key),
+            http,
+            base_url,
+        })
+    }
 }
\ No newline at end of file
@@ -0,0 +1,151 @@
+//! "What would I have paid?" comparisons across tariffs.
+//!
+//! [`compare_tariffs`] prices a period of real consumption against each of
+//! a list of products, using the unit rates and standing charges that
+//! applied in the customer's region at the time, and breaks the result
+//! down by month. Requires the `chrono` feature.
+//!
+//! ```no_run
+//! # async fn run(client: octopust::Client) -> Result<(), octopust::OctopustError> {
+//! use octopust::compare::compare_tariffs;
+//! use octopust::models::ListElectrictyConsumptionQuery;
+//! use octopust::Region;
+//!
+//! let consumption = client
+//!     .list_electricity_consumption(ListElectrictyConsumptionQuery {
+//!         mpan: "2000000000000",
+//!         serial_number: "21L0000000",
+//!         page_size: Some(25000),
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//! let products = ["AGILE-24-10-01", "SILVER-24-12-31", "VAR-22-11-01"];
+//! for tariff in compare_tariffs(&client, &consumption, &products, Region::London).await? {
+//!     println!("{}: {:.2}", tariff.display_name, tariff.total().inc_vat / 100.0);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Utc};
+use futures::TryStreamExt;
+
+use crate::billing::{calculate_cost, BillingOptions, Cost};
+use crate::client::Client;
+use crate::error::OctopustError;
+use crate::models::{ConsumptionReading, ListUnitRatesQuery, RetrieveProductQuery};
+use crate::pagination::PaginationLimits;
+use crate::region::Region;
+use crate::tariff_code::{Fuel, RegisterType, TariffCode};
+
+/// The largest page size the tariff charge endpoints accept.
+const RATES_PAGE_SIZE: u32 = 1500;
+
+/// Costs for one calendar month.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlyCost {
+    pub year: i32,
+    pub month: u32,
+    /// Priced consumption in kWh.
+    pub consumption: f64,
+    pub energy: Cost,
+    pub standing_charge: Cost,
+}
+
+impl MonthlyCost {
+    pub fn total(&self) -> Cost {
+        self.energy + self.standing_charge
+    }
+}
+
+/// What the consumption would have cost on one product.
+#[derive(Debug, Clone)]
+pub struct TariffComparison {
+    pub product_code: String,
+    pub display_name: String,
+    pub tariff_code: TariffCode,
+    pub months: Vec<MonthlyCost>,
+    /// Readings with no published rate, left out of the totals. Non-zero
+    /// when the product was not on sale for all of the period.
+    pub unpriced: usize,
+}
+
+impl TariffComparison {
+    pub fn total(&self) -> Cost {
+        self.months.iter().fold(Cost::default(), |acc, m| acc + m.total())
+    }
+}
+
+/// Price `consumption` on each of `product_codes` in `region`.
+///
+/// Each product is resolved with `retrieve_product` as of the start of the
+/// consumption, so retired products still resolve, and its single-register
+/// electricity tariff for `region` is used. Results are in the order given.
+/// Fails on the first product that cannot be resolved or fetched.
+pub async fn compare_tariffs(
+    client: &Client,
+    consumption: impl AsRef<[ConsumptionReading]>,
+    product_codes: &[&str],
+    region: Region,
+) -> Result<Vec<TariffComparison>, OctopustError> {
+    let consumption = consumption.as_ref();
+    let from = consumption.iter().map(|r| r.interval_start.with_timezone(&Utc)).min();
+    let to = consumption.iter().map(|r| r.interval_end.with_timezone(&Utc)).max();
+    let (Some(from), Some(to)) = (from, to) else {
+        return Err(OctopustError::InvalidInput("no consumption to compare".to_string()));
+    };
+
+    let mut comparisons = Vec::with_capacity(product_codes.len());
+    for &product_code in product_codes {
+        let product = client
+            .retrieve_product(RetrieveProductQuery {
+                product_code,
+                tariffs_active_at: Some(from),
+            })
+            .await?;
+        let tariff = TariffCode::for_product(&product, Fuel::Electricity, RegisterType::Single, region)?;
+
+        let query = || ListUnitRatesQuery {
+            period_from: Some(from),
+            period_to: Some(to),
+            page_size: Some(RATES_PAGE_SIZE),
+            ..ListUnitRatesQuery::for_tariff(&tariff)
+        };
+        let unit_rates: Vec<_> = client
+            .stream_electricity_tariff_standard_unit_rates(query(), PaginationLimits::default())
+            .try_collect()
+            .await?;
+        let standing_charges: Vec<_> = client
+            .stream_electricity_tariff_standing_charges(query(), PaginationLimits::default())
+            .try_collect()
+            .await?;
+
+        let bill = calculate_cost(consumption, &unit_rates, &standing_charges, &BillingOptions::default());
+        let mut months: BTreeMap<(i32, u32), MonthlyCost> = BTreeMap::new();
+        for day in &bill.days {
+            let month = months
+                .entry((day.date.year(), day.date.month()))
+                .or_insert_with(|| MonthlyCost {
+                    year: day.date.year(),
+                    month: day.date.month(),
+                    consumption: 0.0,
+                    energy: Cost::default(),
+                    standing_charge: Cost::default(),
+                });
+            month.consumption += day.consumption;
+            month.energy += day.energy;
+            month.standing_charge += day.standing_charge;
+        }
+
+        comparisons.push(TariffComparison {
+            product_code: product.code,
+            display_name: product.display_name,
+            tariff_code: tariff,
+            months: months.into_values().collect(),
+            unpriced: bill.unpriced.len(),
+        });
+    }
+    Ok(comparisons)
+}
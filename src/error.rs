@@ -1,28 +1,270 @@
+use reqwest::header::HeaderMap;
 use reqwest::StatusCode;
+use std::collections::BTreeMap;
+use std::time::Duration;
 use std::{error, fmt};
 
+use crate::graphql::GraphqlError;
+use crate::region::Region;
+use crate::retry;
+
+/// Every error the crate returns.
+///
+/// Non-exhaustive: the `store` and `arrow` features add variants, and
+/// enabling a feature must not break matches elsewhere in the build.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum OctopustError {
-    Api(ApiError),
-    // ... other error variants ...
+    /// The API returned a non-success status not covered by a more
+    /// specific variant.
+    Api(Box<ApiError>),
+    /// The API rejected the credentials (401 or 403).
+    Auth(Box<ApiError>),
+    /// The requested resource does not exist (404).
+    NotFound(Box<ApiError>),
+    /// The API is throttling requests (429).
+    RateLimited {
+        error: Box<ApiError>,
+        retry_after: Option<Duration>,
+    },
+    /// A successful response whose body did not match the expected model.
+    Decode(DecodeError),
+    /// The request timed out.
+    Timeout(reqwest::Error),
+    /// The GraphQL API returned one or more errors.
+    Graphql(Vec<GraphqlError>),
+    /// A query was rejected before sending, e.g. an empty path segment.
+    InvalidInput(String),
+    /// A postcode straddles more than one region, so it cannot decide the
+    /// tariff on its own. Look the region up from the MPAN instead.
+    AmbiguousPostcode {
+        postcode: String,
+        regions: Vec<Region>,
+    },
+    /// The request could not be sent or its response could not be read.
     Reqwest(reqwest::Error),
+    /// Kept for compatibility; the crate itself now reports a body that
+    /// does not match its model as [`Decode`](Self::Decode).
     Serde(serde_json::Error),
-    // Add other variants as needed
+    /// The client was configured with an invalid setting.
+    Config(String),
+    /// The endpoint needs an API key and the client has none.
+    MissingApiKey,
+    /// The local SQLite store failed.
+    #[cfg(feature = "store")]
+    Store(rusqlite::Error),
+    /// Writing a CSV export failed.
+    Csv(csv::Error),
+    /// Writing a Parquet export failed.
+    #[cfg(feature = "arrow")]
+    Parquet(parquet::errors::ParquetError),
 }
 
 #[derive(Debug)]
 pub struct ApiError {
     pub status: StatusCode,
     pub message: String,
+    /// The `detail` string from a Django REST Framework error body.
+    pub detail: Option<String>,
+    /// Per-parameter errors, e.g. `period_from => ["Invalid datetime."]`.
+    pub field_errors: BTreeMap<String, Vec<String>>,
+    /// Errors not tied to a single parameter (`non_field_errors`).
+    pub non_field_errors: Vec<String>,
+    /// The raw response body.
+    pub body: String,
+}
+
+impl ApiError {
+    /// Build an error from a response body, parsing the Django REST
+    /// Framework error shape when the body is JSON.
+    pub fn from_body(status: StatusCode, body: &str) -> Self {
+        let mut detail = None;
+        let mut field_errors = BTreeMap::new();
+        let mut non_field_errors = Vec::new();
+
+        if let Ok(serde_json::Value::Object(map)) = serde_json::from_str(body) {
+            for (key, value) in map {
+                match key.as_str() {
+                    "detail" => detail = Some(error_strings(value).join(" ")),
+                    "non_field_errors" => non_field_errors = error_strings(value),
+                    _ => {
+                        field_errors.insert(key, error_strings(value));
+                    }
+                }
+            }
+        }
+
+        let message = if let Some(detail) = &detail {
+            detail.clone()
+        } else if !field_errors.is_empty() || !non_field_errors.is_empty() {
+            non_field_errors
+                .iter()
+                .cloned()
+                .chain(field_errors.iter().map(|(field, errors)| format!("{field}: {}", errors.join(" "))))
+                .collect::<Vec<_>>()
+                .join("; ")
+        } else {
+            format!("API returned error status {status}: {body}")
+        };
+
+        ApiError {
+            status,
+            message,
+            detail,
+            field_errors,
+            non_field_errors,
+            body: body.to_string(),
+        }
+    }
+
+    /// Errors reported against a single query parameter.
+    pub fn field_error(&self, field: &str) -> Option<&[String]> {
+        self.field_errors.get(field).map(Vec::as_slice)
+    }
+}
+
+/// Flatten a DRF error value (a string, a list of strings, or a nested
+/// object) into messages.
+fn error_strings(value: serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::String(s) => vec![s],
+        serde_json::Value::Array(values) => values.into_iter().flat_map(error_strings).collect(),
+        serde_json::Value::Object(map) => map
+            .into_iter()
+            .flat_map(|(key, value)| {
+                error_strings(value).into_iter().map(move |e| format!("{key}: {e}"))
+            })
+            .collect(),
+        serde_json::Value::Null => Vec::new(),
+        other => vec![other.to_string()],
+    }
+}
+
+/// Details of a response body that failed to deserialize.
+#[derive(Debug)]
+pub struct DecodeError {
+    pub status: StatusCode,
+    /// What was being parsed, e.g. "products".
+    pub what: String,
+    /// Path to the offending field, e.g. `results[3].valid_from`.
+    pub path: String,
+    /// The raw response body.
+    pub body: String,
+    pub source: serde_json::Error,
+}
+
+impl OctopustError {
+    /// Classify a non-success response by status code.
+    pub(crate) fn from_response(status: StatusCode, headers: &HeaderMap, body: &str) -> Self {
+        let error = Box::new(ApiError::from_body(status, body));
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => OctopustError::Auth(error),
+            StatusCode::NOT_FOUND => OctopustError::NotFound(error),
+            StatusCode::TOO_MANY_REQUESTS => OctopustError::RateLimited {
+                error,
+                retry_after: retry::retry_after(headers),
+            },
+            _ => OctopustError::Api(error),
+        }
+    }
+
+    /// The parsed error response, for variants that carry one.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            OctopustError::Api(err)
+            | OctopustError::Auth(err)
+            | OctopustError::NotFound(err)
+            | OctopustError::RateLimited { error: err, .. } => Some(err),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the response that caused this error, if any.
+    pub fn status(&self) -> Option<StatusCode> {
+        if let Some(err) = self.api_error() {
+            return Some(err.status);
+        }
+        match self {
+            OctopustError::Decode(err) => Some(err.status),
+            OctopustError::Timeout(e) | OctopustError::Reqwest(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    /// How long the server asked us to wait, for rate-limited responses.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            OctopustError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Whether sending the same request again later might succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            OctopustError::RateLimited { .. } | OctopustError::Timeout(_) => true,
+            OctopustError::Api(err) => retry::RetryPolicy::is_retryable_status(err.status),
+            OctopustError::Reqwest(e) => retry::RetryPolicy::is_retryable_error(e),
+            _ => false,
+        }
+    }
+
+    /// Whether the credentials were missing or rejected.
+    pub fn is_auth(&self) -> bool {
+        matches!(self, OctopustError::Auth(_) | OctopustError::MissingApiKey)
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, OctopustError::NotFound(_))
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, OctopustError::RateLimited { .. })
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, OctopustError::Timeout(_))
+    }
+
+    pub fn is_decode(&self) -> bool {
+        matches!(self, OctopustError::Decode(_))
+    }
 }
 
 impl fmt::Display for OctopustError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             OctopustError::Api(err) => write!(f, "API Error ({}): {}", err.status, err.message ),
+            OctopustError::Auth(err) => write!(f, "Authentication failed ({}): {}", err.status, err.message),
+            OctopustError::NotFound(err) => write!(f, "Not found ({}): {}", err.status, err.message),
+            OctopustError::RateLimited { error, retry_after } => match retry_after {
+                Some(wait) => write!(f, "Rate limited, retry after {}s: {}", wait.as_secs(), error.message),
+                None => write!(f, "Rate limited: {}", error.message),
+            },
+            OctopustError::Decode(err) => write!(
+                f,
+                "Failed to parse {} JSON at `{}`: {}. Response body: {}",
+                err.what, err.path, err.source, err.body
+            ),
+            OctopustError::Timeout(e) => write!(f, "Request timed out: {e}"),
+            OctopustError::Graphql(errors) => {
+                let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+                write!(f, "GraphQL error: {}", messages.join("; "))
+            }
+            OctopustError::InvalidInput(msg) => write!(f, "Invalid input: {msg}"),
+            OctopustError::AmbiguousPostcode { postcode, regions } => {
+                let names: Vec<&str> = regions.iter().map(Region::name).collect();
+                write!(f, "Postcode {postcode} spans several regions: {}", names.join(", "))
+            }
             OctopustError::Reqwest(e) => write!(f, "Request error: {e}"),
             OctopustError::Serde(e) => write!(f, "Serialization error: {e}"),
-            // Add display for other variants as needed
+            OctopustError::Config(msg) => write!(f, "Configuration error: {msg}"),
+            OctopustError::MissingApiKey => write!(f, "This endpoint requires an API key, but the client is anonymous"),
+            #[cfg(feature = "store")]
+            OctopustError::Store(e) => write!(f, "Store error: {e}"),
+            OctopustError::Csv(e) => write!(f, "CSV export error: {e}"),
+            #[cfg(feature = "arrow")]
+            OctopustError::Parquet(e) => write!(f, "Parquet export error: {e}"),
         }
     }
 }
@@ -30,8 +272,15 @@ impl fmt::Display for OctopustError {
 impl error::Error for OctopustError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
+            OctopustError::Decode(err) => Some(&err.source),
+            OctopustError::Timeout(e) => Some(e),
             OctopustError::Reqwest(e) => Some(e),
             OctopustError::Serde(e) => Some(e),
+            #[cfg(feature = "store")]
+            OctopustError::Store(e) => Some(e),
+            OctopustError::Csv(e) => Some(e),
+            #[cfg(feature = "arrow")]
+            OctopustError::Parquet(e) => Some(e),
             _ => None,
         }
     }
@@ -39,7 +288,11 @@ impl error::Error for OctopustError {
 
 impl From<reqwest::Error> for OctopustError {
     fn from(e: reqwest::Error) -> Self {
-        OctopustError::Reqwest(e)
+        if e.is_timeout() {
+            OctopustError::Timeout(e)
+        } else {
+            OctopustError::Reqwest(e)
+        }
     }
 }
 
@@ -47,4 +300,24 @@ impl From<serde_json::Error> for OctopustError {
     fn from(e: serde_json::Error) -> Self {
         OctopustError::Serde(e)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "store")]
+impl From<rusqlite::Error> for OctopustError {
+    fn from(e: rusqlite::Error) -> Self {
+        OctopustError::Store(e)
+    }
+}
+
+impl From<csv::Error> for OctopustError {
+    fn from(e: csv::Error) -> Self {
+        OctopustError::Csv(e)
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<parquet::errors::ParquetError> for OctopustError {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        OctopustError::Parquet(e)
+    }
+}
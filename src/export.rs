@@ -0,0 +1,318 @@
+//! Export of consumption and tariff charge series for analysis tools.
+//!
+//! [`CsvWriter`] writes [`ConsumptionReading`] and [`TariffCharge`] rows as
+//! CSV. With the `arrow` feature, `ParquetWriter` writes them as Parquet
+//! with float columns and UTC microsecond timestamp columns, ready for
+//! pandas, Polars or DuckDB.
+//!
+//! Both writers take rows one at a time or a whole `stream_*` stream, so
+//! an export across many pages holds at most one page in memory (plus, for
+//! Parquet, the row group being encoded).
+//!
+//! ```no_run
+//! # async fn run(client: octopust::Client) -> Result<(), octopust::OctopustError> {
+//! use std::fs::File;
+//! use std::io::BufWriter;
+//!
+//! use octopust::export::CsvWriter;
+//! use octopust::models::ListElectrictyConsumptionQuery;
+//!
+//! let file = BufWriter::new(File::create("consumption.csv").expect("create file"));
+//! let mut csv = CsvWriter::new(file)?;
+//! let readings = client.stream_electricity_consumption(
+//!     ListElectrictyConsumptionQuery {
+//!         mpan: "2000000000000",
+//!         serial_number: "21L0000000",
+//!         ..Default::default()
+//!     },
+//!     Default::default(),
+//! );
+//! let written = csv.write_stream(readings).await?;
+//! csv.finish()?;
+//! println!("{written} readings");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::Write;
+use std::marker::PhantomData;
+
+use futures::{Stream, TryStreamExt};
+
+use crate::error::OctopustError;
+use crate::models::{ConsumptionReading, TariffCharge, Timestamp};
+
+#[cfg(feature = "arrow")]
+use std::sync::Arc;
+
+#[cfg(feature = "arrow")]
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+#[cfg(feature = "arrow")]
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+#[cfg(feature = "arrow")]
+use parquet::arrow::ArrowWriter;
+#[cfg(feature = "arrow")]
+use parquet::basic::Compression;
+#[cfg(feature = "arrow")]
+use parquet::file::properties::WriterProperties;
+
+/// Rows are handed to the Parquet encoder in batches of this size.
+#[cfg(feature = "arrow")]
+const BATCH_SIZE: usize = 8192;
+
+/// Rows per Parquet row group; bounds how much encoded data is buffered
+/// before it is written out.
+#[cfg(feature = "arrow")]
+const ROW_GROUP_SIZE: usize = 128 * 1024;
+
+/// A record that can be exported as a row.
+///
+/// Implemented for [`ConsumptionReading`] and [`TariffCharge`]. Column
+/// names match the API's field names.
+pub trait ExportRow: Sized {
+    /// Column names, in order.
+    const COLUMNS: &'static [&'static str];
+
+    /// The row's fields formatted as CSV cells. Timestamps are RFC 3339
+    /// with the offset the API sent; missing values are empty.
+    fn csv_record(&self) -> Vec<String>;
+
+    /// The Arrow schema of an exported batch.
+    #[cfg(feature = "arrow")]
+    fn arrow_schema() -> Schema;
+
+    /// Convert rows into a record batch with [`Self::arrow_schema`].
+    #[cfg(feature = "arrow")]
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch, ArrowError>;
+}
+
+impl ExportRow for ConsumptionReading {
+    const COLUMNS: &'static [&'static str] = &["consumption", "interval_start", "interval_end"];
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![
+            self.consumption.to_string(),
+            csv_timestamp(&self.interval_start),
+            csv_timestamp(&self.interval_end),
+        ]
+    }
+
+    #[cfg(feature = "arrow")]
+    fn arrow_schema() -> Schema {
+        Schema::new(vec![
+            Field::new("consumption", DataType::Float64, false),
+            Field::new("interval_start", timestamp_type(), false),
+            Field::new("interval_end", timestamp_type(), false),
+        ])
+    }
+
+    #[cfg(feature = "arrow")]
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch, ArrowError> {
+        let consumption: Float64Array = rows.iter().map(|r| Some(r.consumption)).collect();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(consumption),
+            Arc::new(timestamps(rows.iter().map(|r| Some(&r.interval_start)))),
+            Arc::new(timestamps(rows.iter().map(|r| Some(&r.interval_end)))),
+        ];
+        RecordBatch::try_new(Arc::new(Self::arrow_schema()), columns)
+    }
+}
+
+impl ExportRow for TariffCharge {
+    const COLUMNS: &'static [&'static str] =
+        &["value_exc_vat", "value_inc_vat", "valid_from", "valid_to", "payment_method"];
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![
+            self.value_exc_vat.to_string(),
+            self.value_inc_vat.to_string(),
+            csv_timestamp(&self.valid_from),
+            self.valid_to.as_ref().map(csv_timestamp).unwrap_or_default(),
+            self.payment_method.map(|m| m.as_str().to_string()).unwrap_or_default(),
+        ]
+    }
+
+    #[cfg(feature = "arrow")]
+    fn arrow_schema() -> Schema {
+        Schema::new(vec![
+            Field::new("value_exc_vat", DataType::Float64, false),
+            Field::new("value_inc_vat", DataType::Float64, false),
+            Field::new("valid_from", timestamp_type(), false),
+            Field::new("valid_to", timestamp_type(), true),
+            Field::new("payment_method", DataType::Utf8, true),
+        ])
+    }
+
+    #[cfg(feature = "arrow")]
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch, ArrowError> {
+        let exc_vat: Float64Array = rows.iter().map(|r| Some(r.value_exc_vat)).collect();
+        let inc_vat: Float64Array = rows.iter().map(|r| Some(r.value_inc_vat)).collect();
+        let payment_method: StringArray = rows.iter().map(|r| r.payment_method.map(|m| m.as_str())).collect();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(exc_vat),
+            Arc::new(inc_vat),
+            Arc::new(timestamps(rows.iter().map(|r| Some(&r.valid_from)))),
+            Arc::new(timestamps(rows.iter().map(|r| r.valid_to.as_ref()))),
+            Arc::new(payment_method),
+        ];
+        RecordBatch::try_new(Arc::new(Self::arrow_schema()), columns)
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn csv_timestamp(timestamp: &Timestamp) -> String {
+    timestamp.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+}
+
+#[cfg(not(feature = "chrono"))]
+fn csv_timestamp(timestamp: &Timestamp) -> String {
+    timestamp.clone()
+}
+
+#[cfg(feature = "arrow")]
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+}
+
+#[cfg(feature = "arrow")]
+fn timestamps<'a>(values: impl Iterator<Item = Option<&'a Timestamp>>) -> TimestampMicrosecondArray {
+    values
+        .map(|t| t.map(|t| t.timestamp_micros()))
+        .collect::<TimestampMicrosecondArray>()
+        .with_timezone("UTC")
+}
+
+/// Writes rows as CSV with a header row.
+///
+/// `out` is written to synchronously; wrap files in a `BufWriter`. Call
+/// [`finish`](CsvWriter::finish) to flush the last rows.
+#[derive(Debug)]
+pub struct CsvWriter<W: Write, T> {
+    writer: csv::Writer<W>,
+    rows: u64,
+    row_type: PhantomData<fn(&T)>,
+}
+
+impl<W: Write, T: ExportRow> CsvWriter<W, T> {
+    /// Start a CSV document on `out`, writing the header straight away so
+    /// that an empty export is still a valid file.
+    pub fn new(out: W) -> Result<Self, OctopustError> {
+        let mut writer = csv::Writer::from_writer(out);
+        writer.write_record(T::COLUMNS)?;
+        Ok(CsvWriter { writer, rows: 0, row_type: PhantomData })
+    }
+
+    /// Write one row.
+    pub fn write(&mut self, row: &T) -> Result<(), OctopustError> {
+        self.writer.write_record(row.csv_record())?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Write every row of a stream, returning how many were written.
+    ///
+    /// Stops at the first error; rows before it have already been written.
+    pub async fn write_stream<S>(&mut self, stream: S) -> Result<u64, OctopustError>
+    where
+        S: Stream<Item = Result<T, OctopustError>>,
+    {
+        let mut stream = Box::pin(stream);
+        let mut written = 0;
+        while let Some(row) = stream.try_next().await? {
+            self.write(&row)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Rows written so far, not counting the header.
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn finish(self) -> Result<W, OctopustError> {
+        self.writer.into_inner().map_err(|e| OctopustError::Csv(e.into_error().into()))
+    }
+}
+
+/// Writes rows as a Snappy-compressed Parquet file.
+///
+/// Rows are buffered into batches of 8192 before being encoded. The file
+/// is only complete once [`finish`](ParquetWriter::finish) has written the
+/// footer. Requires the `arrow` feature.
+#[cfg(feature = "arrow")]
+pub struct ParquetWriter<W: Write + Send, T> {
+    writer: ArrowWriter<W>,
+    buffer: Vec<T>,
+    rows: u64,
+}
+
+#[cfg(feature = "arrow")]
+impl<W: Write + Send, T> std::fmt::Debug for ParquetWriter<W, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParquetWriter")
+            .field("buffered", &self.buffer.len())
+            .field("rows", &self.rows)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl<W: Write + Send, T: ExportRow + Clone> ParquetWriter<W, T> {
+    /// Start a Parquet file on `out`.
+    pub fn new(out: W) -> Result<Self, OctopustError> {
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_max_row_group_size(ROW_GROUP_SIZE)
+            .build();
+        let writer = ArrowWriter::try_new(out, Arc::new(T::arrow_schema()), Some(props))?;
+        Ok(ParquetWriter { writer, buffer: Vec::with_capacity(BATCH_SIZE), rows: 0 })
+    }
+
+    /// Write one row.
+    pub fn write(&mut self, row: &T) -> Result<(), OctopustError> {
+        self.buffer.push(row.clone());
+        self.rows += 1;
+        if self.buffer.len() >= BATCH_SIZE {
+            self.flush_batch()?;
+        }
+        Ok(())
+    }
+
+    /// Write every row of a stream, returning how many were written.
+    pub async fn write_stream<S>(&mut self, stream: S) -> Result<u64, OctopustError>
+    where
+        S: Stream<Item = Result<T, OctopustError>>,
+    {
+        let mut stream = Box::pin(stream);
+        let mut written = 0;
+        while let Some(row) = stream.try_next().await? {
+            self.write(&row)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Rows written so far.
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// Write any buffered rows and the file footer, and return the
+    /// underlying writer.
+    pub fn finish(mut self) -> Result<W, OctopustError> {
+        self.flush_batch()?;
+        Ok(self.writer.into_inner()?)
+    }
+
+    fn flush_batch(&mut self) -> Result<(), OctopustError> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let batch = T::record_batch(&self.buffer).map_err(parquet::errors::ParquetError::from)?;
+        self.writer.write(&batch)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+//! Account balance and bills.
+
+use serde::Deserialize;
+
+use super::models::{AccountBalance, Bill, Connection};
+use super::Kraken;
+use crate::api;
+use crate::error::OctopustError;
+
+const ACCOUNT_BALANCE: &str = "query AccountBalance($accountNumber: String!) {
+  account(accountNumber: $accountNumber) {
+    number
+    balance
+    overdueBalance
+  }
+}";
+
+const BILLS: &str = "query Bills($accountNumber: String!, $first: Int!) {
+  account(accountNumber: $accountNumber) {
+    bills(first: $first) {
+      edges {
+        node {
+          id
+          billType
+          fromDate
+          toDate
+          issuedDate
+        }
+      }
+    }
+  }
+}";
+
+#[derive(Deserialize)]
+struct AccountData<T> {
+    account: T,
+}
+
+#[derive(Deserialize)]
+struct BillsAccount {
+    bills: Connection<Bill>,
+}
+
+impl Kraken {
+    /// Current balance of an account.
+    pub async fn account_balance(&self, account_number: &str) -> Result<AccountBalance, OctopustError> {
+        api::require_non_empty("account_number", account_number)?;
+        let variables = serde_json::json!({ "accountNumber": account_number });
+        let data: AccountData<AccountBalance> = self.execute(ACCOUNT_BALANCE, variables).await?;
+        Ok(data.account)
+    }
+
+    /// The most recent `first` bills on an account, newest first.
+    pub async fn bills(&self, account_number: &str, first: u32) -> Result<Vec<Bill>, OctopustError> {
+        api::require_non_empty("account_number", account_number)?;
+        let variables = serde_json::json!({ "accountNumber": account_number, "first": first });
+        let data: AccountData<BillsAccount> = self.execute(BILLS, variables).await?;
+        Ok(data.account.bills.into_nodes())
+    }
+}
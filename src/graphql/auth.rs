@@ -0,0 +1,105 @@
+//! `obtainKrakenToken` login and JWT caching.
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use super::Kraken;
+use crate::error::OctopustError;
+
+/// Refresh the token this long before it expires.
+const REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// Assumed lifetime when the token payload carries no `exp`.
+const DEFAULT_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+const OBTAIN_TOKEN: &str = "mutation ObtainKrakenToken($input: ObtainJSONWebTokenInput!) {
+  obtainKrakenToken(input: $input) {
+    token
+    payload
+  }
+}";
+
+struct CachedToken {
+    token: String,
+    expires_at: SystemTime,
+}
+
+pub(crate) struct TokenCache {
+    api_key: Option<String>,
+    cached: Mutex<Option<CachedToken>>,
+}
+
+// Keep the API key and token out of debug output.
+impl fmt::Debug for TokenCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenCache")
+            .field("has_api_key", &self.api_key.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObtainTokenData {
+    obtain_kraken_token: ObtainedToken,
+}
+
+#[derive(Deserialize)]
+struct ObtainedToken {
+    token: String,
+    #[serde(default)]
+    payload: Option<TokenPayload>,
+}
+
+#[derive(Deserialize)]
+struct TokenPayload {
+    exp: Option<u64>,
+}
+
+impl TokenCache {
+    pub(crate) fn new(api_key: Option<String>) -> Self {
+        TokenCache {
+            api_key,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Return a cached token, logging in again if it is missing or close
+    /// to expiry. The lock is held while logging in so concurrent callers
+    /// share one `obtainKrakenToken` request.
+    pub(crate) async fn token(&self, kraken: &Kraken) -> Result<String, OctopustError> {
+        let api_key = self.api_key.as_deref().ok_or(OctopustError::MissingApiKey)?;
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if SystemTime::now() + REFRESH_MARGIN < token.expires_at {
+                return Ok(token.token.clone());
+            }
+        }
+
+        let variables = serde_json::json!({ "input": { "APIKey": api_key } });
+        let data: ObtainTokenData = kraken.send(OBTAIN_TOKEN, &variables, None).await?;
+        let obtained = data.obtain_kraken_token;
+        let expires_at = obtained
+            .payload
+            .and_then(|p| p.exp)
+            .map(|exp| UNIX_EPOCH + Duration::from_secs(exp))
+            .unwrap_or_else(|| SystemTime::now() + DEFAULT_LIFETIME);
+
+        *cached = Some(CachedToken {
+            token: obtained.token.clone(),
+            expires_at,
+        });
+        Ok(obtained.token)
+    }
+
+    /// Drop `token` from the cache if it is still the current one.
+    pub(crate) async fn invalidate(&self, token: &str) {
+        let mut cached = self.cached.lock().await;
+        if cached.as_ref().is_some_and(|c| c.token == token) {
+            *cached = None;
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Intelligent Octopus planned and completed dispatches.
+//!
+//! Outside the normal off-peak window, Intelligent Octopus Go bills any
+//! half-hour slot that overlaps a smart-charging dispatch at the off-peak
+//! rate. [`effective_rates`] applies that rule to a set of standard unit
+//! rates (requires the `chrono` feature).
+
+use serde::{Deserialize, Deserializer};
+
+use super::models::Dispatch;
+use super::Kraken;
+use crate::api;
+use crate::error::OctopustError;
+
+const PLANNED_DISPATCHES: &str = "query PlannedDispatches($accountNumber: String!) {
+  plannedDispatches(accountNumber: $accountNumber) {
+    start
+    end
+    delta
+    meta {
+      source
+      location
+    }
+  }
+}";
+
+const COMPLETED_DISPATCHES: &str = "query CompletedDispatches($accountNumber: String!) {
+  completedDispatches(accountNumber: $accountNumber) {
+    start
+    end
+    delta
+    meta {
+      source
+      location
+    }
+  }
+}";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlannedData {
+    planned_dispatches: Vec<Dispatch>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletedData {
+    completed_dispatches: Vec<Dispatch>,
+}
+
+impl Kraken {
+    /// Dispatches scheduled for the account's Intelligent device.
+    pub async fn planned_dispatches(&self, account_number: &str) -> Result<Vec<Dispatch>, OctopustError> {
+        api::require_non_empty("account_number", account_number)?;
+        let variables = serde_json::json!({ "accountNumber": account_number });
+        let data: PlannedData = self.execute(PLANNED_DISPATCHES, variables).await?;
+        Ok(data.planned_dispatches)
+    }
+
+    /// Dispatches that have already taken place.
+    pub async fn completed_dispatches(&self, account_number: &str) -> Result<Vec<Dispatch>, OctopustError> {
+        api::require_non_empty("account_number", account_number)?;
+        let variables = serde_json::json!({ "accountNumber": account_number });
+        let data: CompletedData = self.execute(COMPLETED_DISPATCHES, variables).await?;
+        Ok(data.completed_dispatches)
+    }
+}
+
+/// Kraken sends `delta` as a decimal string; accept numbers too.
+pub(crate) fn deserialize_delta<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Delta {
+        Number(f64),
+        Text(String),
+    }
+    match Delta::deserialize(deserializer)? {
+        Delta::Number(n) => Ok(n),
+        Delta::Text(s) => s.trim().parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(feature = "chrono")]
+pub use effective::{effective_rates, EffectiveRate};
+
+#[cfg(feature = "chrono")]
+mod effective {
+    use chrono::{DateTime, Duration, DurationRound, Utc};
+
+    use crate::graphql::models::Dispatch;
+    use crate::models::{PaymentMethod, TariffCharge};
+
+    /// The price that applies to one half-hour slot.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct EffectiveRate {
+        pub start: DateTime<Utc>,
+        pub end: DateTime<Utc>,
+        pub value_exc_vat: f64,
+        pub value_inc_vat: f64,
+        /// Whether a dispatch moved this slot to the off-peak rate. A
+        /// dispatch over a slot that is already off-peak leaves it `false`.
+        pub dispatched: bool,
+    }
+
+    /// Price every half-hour slot in `[from, to)`, applying dispatches.
+    ///
+    /// Only rates for `payment_method`, or without a payment method, are
+    /// used, as in [`calculate_cost`](crate::billing::calculate_cost).
+    /// Each slot takes the standard rate in force at its start; where rates
+    /// overlap, the one with the latest `valid_from` wins. If a dispatch
+    /// overlaps the slot, it is priced at the off-peak rate instead: the
+    /// cheapest rate in force at some point within a day either side of
+    /// the slot. That finds the daily off-peak window without picking up a
+    /// rate from a different price period. Slots not covered by any rate
+    /// are left out.
+    pub fn effective_rates(
+        rates: &[TariffCharge],
+        dispatches: &[Dispatch],
+        payment_method: PaymentMethod,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<EffectiveRate> {
+        let rates: Vec<&TariffCharge> = rates
+            .iter()
+            .filter(|rate| rate.payment_method.is_none_or(|m| m == payment_method))
+            .collect();
+        let slot = Duration::minutes(30);
+        let mut out = Vec::new();
+        let mut start = from.duration_trunc(slot).unwrap_or(from);
+        while start < to {
+            let end = start + slot;
+            let standard = rates
+                .iter()
+                .filter(|rate| rate.valid_from <= start && rate.valid_to.is_none_or(|valid_to| start < valid_to))
+                .max_by_key(|rate| rate.valid_from)
+                .copied();
+            if let Some(standard) = standard {
+                let overlapped = dispatches.iter().any(|d| d.start < end && start < d.end);
+                let off_peak = overlapped
+                    .then(|| off_peak_rate(&rates, start, end))
+                    .flatten()
+                    .filter(|off_peak| off_peak.value_inc_vat < standard.value_inc_vat);
+                let price = off_peak.unwrap_or(standard);
+                out.push(EffectiveRate {
+                    start,
+                    end,
+                    value_exc_vat: price.value_exc_vat,
+                    value_inc_vat: price.value_inc_vat,
+                    dispatched: off_peak.is_some(),
+                });
+            }
+            start = end;
+        }
+        out
+    }
+
+    /// The cheapest rate in force at any point within a day of the slot.
+    fn off_peak_rate<'a>(
+        rates: &[&'a TariffCharge],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Option<&'a TariffCharge> {
+        let (from, to) = (start - Duration::days(1), end + Duration::days(1));
+        rates
+            .iter()
+            .copied()
+            .filter(|rate| rate.valid_from < to && rate.valid_to.is_none_or(|valid_to| from < valid_to))
+            .min_by(|a, b| a.value_inc_vat.total_cmp(&b.value_inc_vat))
+    }
+}
@@ -0,0 +1,152 @@
+//! Client for the Kraken GraphQL API.
+//!
+//! Some Octopus features (account balance, bills, Intelligent dispatches,
+//! Home Mini telemetry) are only available over GraphQL. [`Kraken`] shares
+//! the HTTP transport of the [`Client`](crate::Client) it came from, logs in
+//! with `obtainKrakenToken` using the client's API key, and caches the JWT
+//! until shortly before it expires.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), octopust::OctopustError> {
+//! let client = octopust::Client::new("API_KEY");
+//! let balance = client.kraken().account_balance("A-1234ABCD").await?;
+//! println!("balance: {}p", balance.balance);
+//! # Ok(())
+//! # }
+//! ```
+
+mod account;
+mod auth;
+pub mod dispatches;
+pub mod models;
+pub mod telemetry;
+
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{DecodeError, OctopustError};
+use crate::http::Http;
+use auth::TokenCache;
+
+pub const DEFAULT_GRAPHQL_URL: &str = "https://api.octopus.energy/v1/graphql/";
+
+/// Kraken error code for an expired JWT.
+const EXPIRED_TOKEN_CODE: &str = "KT-CT-1124";
+
+/// Handle for the Kraken GraphQL API, obtained from `Client::kraken`.
+///
+/// Clones share the cached token.
+#[derive(Debug, Clone)]
+pub struct Kraken {
+    http: Arc<Http>,
+    url: String,
+    tokens: Arc<TokenCache>,
+}
+
+/// A single entry of a GraphQL `errors` array.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GraphqlError {
+    pub message: String,
+    #[serde(default)]
+    pub path: Vec<Value>,
+    #[serde(default)]
+    pub extensions: GraphqlErrorExtensions,
+}
+
+/// Kraken-specific error details.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphqlErrorExtensions {
+    /// A Kraken error code such as `KT-CT-1124`.
+    pub error_code: Option<String>,
+    pub error_description: Option<String>,
+}
+
+impl GraphqlError {
+    pub fn code(&self) -> Option<&str> {
+        self.extensions.error_code.as_deref()
+    }
+}
+
+#[derive(Deserialize)]
+struct GraphqlResponse {
+    data: Option<Value>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+impl Kraken {
+    pub(crate) fn new(http: Arc<Http>, url: String, api_key: Option<String>) -> Self {
+        Kraken {
+            http,
+            url,
+            tokens: Arc::new(TokenCache::new(api_key)),
+        }
+    }
+
+    /// The GraphQL endpoint this handle talks to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Run an authenticated query or mutation and deserialize its `data`.
+    ///
+    /// If the server reports that the token has expired, a fresh one is
+    /// obtained and the request is sent once more.
+    pub async fn execute<T: DeserializeOwned>(
+        &self,
+        document: &str,
+        variables: Value,
+    ) -> Result<T, OctopustError> {
+        let token = self.tokens.token(self).await?;
+        match self.send(document, &variables, Some(&token)).await {
+            Err(OctopustError::Graphql(errors))
+                if errors.iter().any(|e| e.code() == Some(EXPIRED_TOKEN_CODE)) =>
+            {
+                self.tokens.invalidate(&token).await;
+                let token = self.tokens.token(self).await?;
+                self.send(document, &variables, Some(&token)).await
+            }
+            result => result,
+        }
+    }
+
+    /// Send a GraphQL request, without the token cache.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        document: &str,
+        variables: &Value,
+        token: Option<&str>,
+    ) -> Result<T, OctopustError> {
+        let body = serde_json::json!({ "query": document, "variables": variables });
+        let mut req = self.http.post(&self.url).json(&body);
+        if let Some(token) = token {
+            req = req.header(reqwest::header::AUTHORIZATION, token);
+        }
+        let resp = self.http.send(req).await?;
+        let body_str = String::from_utf8_lossy(&resp.body);
+        if !resp.status.is_success() {
+            return Err(OctopustError::from_response(resp.status, &resp.headers, &body_str));
+        }
+
+        let decode_error = |e: serde_path_to_error::Error<serde_json::Error>| {
+            OctopustError::Decode(DecodeError {
+                status: resp.status,
+                what: "GraphQL".to_string(),
+                path: e.path().to_string(),
+                body: body_str.to_string(),
+                source: e.into_inner(),
+            })
+        };
+        let de = &mut serde_json::Deserializer::from_slice(&resp.body);
+        let parsed: GraphqlResponse = serde_path_to_error::deserialize(de).map_err(decode_error)?;
+        if !parsed.errors.is_empty() {
+            return Err(OctopustError::Graphql(parsed.errors));
+        }
+        let data = parsed.data.unwrap_or(Value::Null);
+        serde_path_to_error::deserialize(data).map_err(decode_error)
+    }
+}
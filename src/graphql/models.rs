@@ -0,0 +1,91 @@
+//! Data types returned by the Kraken GraphQL API.
+
+use serde::Deserialize;
+
+use crate::models::Timestamp;
+
+/// An account's balance, in pence. Positive means in credit.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalance {
+    pub number: String,
+    pub balance: i64,
+    pub overdue_balance: Option<i64>,
+}
+
+/// A bill or statement issued on an account.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bill {
+    pub id: String,
+    pub bill_type: Option<String>,
+    /// Start of the billed period, as `YYYY-MM-DD`.
+    pub from_date: Option<String>,
+    /// End of the billed period, as `YYYY-MM-DD`.
+    pub to_date: Option<String>,
+    pub issued_date: Option<String>,
+}
+
+/// An Intelligent Octopus smart-charging dispatch.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Dispatch {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    /// Energy delivered during the dispatch, in kWh. Charging is negative.
+    #[serde(rename = "delta", deserialize_with = "super::dispatches::deserialize_delta")]
+    pub delta_kwh: f64,
+    #[serde(default)]
+    pub meta: Option<DispatchMeta>,
+}
+
+impl Dispatch {
+    /// What triggered the dispatch, e.g. `smart-charge` or `bump-charge`.
+    pub fn source(&self) -> Option<&str> {
+        self.meta.as_ref()?.source.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchMeta {
+    pub source: Option<String>,
+    pub location: Option<String>,
+}
+
+/// A single Home Mini reading.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryReading {
+    pub read_at: Timestamp,
+    /// Cumulative meter reading, in Wh.
+    #[serde(default, deserialize_with = "super::telemetry::deserialize_optional_number")]
+    pub consumption: Option<f64>,
+    /// Instantaneous demand, in W.
+    #[serde(default, deserialize_with = "super::telemetry::deserialize_optional_number")]
+    pub demand: Option<f64>,
+    /// Energy used since the previous reading, in Wh.
+    #[serde(default, deserialize_with = "super::telemetry::deserialize_optional_number")]
+    pub consumption_delta: Option<f64>,
+    /// Cost of `consumption_delta` excluding VAT, in pence.
+    #[serde(default, deserialize_with = "super::telemetry::deserialize_optional_number")]
+    pub cost_delta: Option<f64>,
+    /// Cost of `consumption_delta` including VAT, in pence.
+    #[serde(default, deserialize_with = "super::telemetry::deserialize_optional_number")]
+    pub cost_delta_with_tax: Option<f64>,
+}
+
+/// A Relay-style connection, as used by Kraken for paged lists.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Connection<T> {
+    pub(crate) edges: Vec<Edge<T>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Edge<T> {
+    pub(crate) node: T,
+}
+
+impl<T> Connection<T> {
+    pub(crate) fn into_nodes(self) -> Vec<T> {
+        self.edges.into_iter().map(|e| e.node).collect()
+    }
+}
@@ -0,0 +1,202 @@
+//! Octopus Home Mini live telemetry.
+//!
+//! Readings are keyed by the Home Mini's device ID, which appears under the
+//! electricity meter's smart devices in the Octopus dashboard.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::models::TelemetryReading;
+use super::Kraken;
+use crate::api;
+use crate::error::OctopustError;
+use crate::models::{QueryTimestamp, Timestamp};
+
+const SMART_METER_TELEMETRY: &str = "query SmartMeterTelemetry(
+  $deviceId: String!
+  $start: DateTime
+  $end: DateTime
+  $grouping: TelemetryGrouping
+) {
+  smartMeterTelemetry(deviceId: $deviceId, start: $start, end: $end, grouping: $grouping) {
+    readAt
+    consumption
+    demand
+    consumptionDelta
+    costDelta
+    costDeltaWithTax
+  }
+}";
+
+/// Aggregation applied to telemetry readings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TelemetryGrouping {
+    TenSeconds,
+    OneMinute,
+    FiveMinutes,
+    ThirtyMinutes,
+    OneHour,
+}
+
+/// Parameters for [`Kraken::smart_meter_telemetry`]. Without `start` the
+/// API returns only the latest reading.
+#[derive(Default)]
+pub struct TelemetryQuery<'a> {
+    pub device_id: &'a str,
+    pub start: Option<QueryTimestamp<'a>>,
+    pub end: Option<QueryTimestamp<'a>>,
+    pub grouping: Option<TelemetryGrouping>,
+}
+
+/// How [`Kraken::poll_telemetry`] paces its requests.
+#[derive(Debug, Clone, Copy)]
+pub struct TelemetryPollOptions {
+    /// Delay between polls while new readings keep arriving.
+    pub interval: Duration,
+    /// Upper bound for the delay, which doubles after each poll that
+    /// returns nothing new.
+    pub max_interval: Duration,
+    pub grouping: Option<TelemetryGrouping>,
+}
+
+impl Default for TelemetryPollOptions {
+    fn default() -> Self {
+        TelemetryPollOptions {
+            interval: Duration::from_secs(10),
+            max_interval: Duration::from_secs(5 * 60),
+            grouping: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TelemetryData {
+    smart_meter_telemetry: Option<Vec<TelemetryReading>>,
+}
+
+struct PollState {
+    kraken: Kraken,
+    device_id: String,
+    options: TelemetryPollOptions,
+    delay: Option<Duration>,
+    pending: VecDeque<TelemetryReading>,
+    last_seen: Option<Timestamp>,
+}
+
+impl Kraken {
+    /// Telemetry readings from a Home Mini for a time range.
+    pub async fn smart_meter_telemetry(
+        &self,
+        query: TelemetryQuery<'_>,
+    ) -> Result<Vec<TelemetryReading>, OctopustError> {
+        api::require_non_empty("device_id", query.device_id)?;
+        let variables = serde_json::json!({
+            "deviceId": query.device_id,
+            "start": query.start.as_ref().map(api::format_timestamp),
+            "end": query.end.as_ref().map(api::format_timestamp),
+            "grouping": query.grouping,
+        });
+        let data: TelemetryData = self.execute(SMART_METER_TELEMETRY, variables).await?;
+        Ok(data.smart_meter_telemetry.unwrap_or_default())
+    }
+
+    /// Poll a Home Mini for new readings.
+    ///
+    /// The first poll fetches the latest reading; each later one asks for
+    /// every reading since the newest already seen. Each reading is
+    /// yielded once, in `read_at` order. When a poll brings
+    /// nothing new the delay doubles, up to `max_interval`, and returns to
+    /// `interval` once data flows again. Transient errors (see
+    /// [`OctopustError::is_retryable`]) are yielded and polling carries on;
+    /// any other error, such as a rejected API key, is yielded and ends the
+    /// stream.
+    pub fn poll_telemetry(
+        &self,
+        device_id: &str,
+        options: TelemetryPollOptions,
+    ) -> impl Stream<Item = Result<TelemetryReading, OctopustError>> + 'static {
+        let state = PollState {
+            kraken: self.clone(),
+            device_id: device_id.to_string(),
+            options,
+            delay: None,
+            pending: VecDeque::new(),
+            last_seen: None,
+        };
+
+        stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+            loop {
+                if let Some(reading) = state.pending.pop_front() {
+                    return Some((Ok(reading), Some(state)));
+                }
+                if let Some(delay) = state.delay {
+                    tokio::time::sleep(delay).await;
+                }
+
+                // Ask for everything since the newest reading seen, so
+                // readings between slow polls are not lost.
+                #[cfg(feature = "chrono")]
+                let start = state.last_seen.map(|seen| seen.with_timezone(&chrono::Utc));
+                #[cfg(not(feature = "chrono"))]
+                let start = state.last_seen.as_deref();
+                let query = TelemetryQuery {
+                    device_id: &state.device_id,
+                    start,
+                    end: None,
+                    grouping: state.options.grouping,
+                };
+                let result = state.kraken.smart_meter_telemetry(query).await;
+                let backoff = state
+                    .delay
+                    .map_or(state.options.interval, |d| (d * 2).min(state.options.max_interval));
+
+                let readings = match result {
+                    Ok(readings) => readings,
+                    Err(e) if e.is_retryable() => {
+                        state.delay = Some(backoff);
+                        return Some((Err(e), Some(state)));
+                    }
+                    Err(e) => return Some((Err(e), None)),
+                };
+                // Keyed by timestamp: sorted, and each reading kept once.
+                let fresh: BTreeMap<Timestamp, TelemetryReading> = readings
+                    .into_iter()
+                    .filter(|r| state.last_seen.as_ref().is_none_or(|seen| &r.read_at > seen))
+                    .map(|r| (r.read_at.to_owned(), r))
+                    .collect();
+
+                match fresh.keys().next_back() {
+                    Some(latest) => {
+                        state.last_seen = Some(latest.to_owned());
+                        state.delay = Some(state.options.interval);
+                        state.pending.extend(fresh.into_values());
+                    }
+                    None => state.delay = Some(backoff),
+                }
+            }
+        })
+    }
+}
+
+/// Kraken sends some telemetry numbers as decimal strings.
+pub(crate) fn deserialize_optional_number<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Number {
+        Number(f64),
+        Text(String),
+    }
+    match Option::<Number>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Number::Number(n)) => Ok(Some(n)),
+        Some(Number::Text(s)) => s.trim().parse().map(Some).map_err(serde::de::Error::custom),
+    }
+}
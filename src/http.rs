@@ -0,0 +1,153 @@
+//! Shared HTTP transport used by the `api` functions.
+
+use bytes::Bytes;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Client as HttpClient, Method, Request, RequestBuilder, StatusCode};
+use tokio::sync::Semaphore;
+
+use crate::cache::Cache;
+use crate::error::OctopustError;
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+
+/// A `reqwest::Client` plus the credentials, retry policy and limits
+/// applied to every request.
+///
+/// Authentication is applied per request rather than through default
+/// headers so that a caller-supplied `reqwest::Client` works unchanged.
+/// `Client` keeps this behind an `Arc`, so the rate limiter and in-flight
+/// cap are shared by all of its clones.
+#[derive(Debug)]
+pub struct Http {
+    client: HttpClient,
+    auth: Option<HeaderValue>,
+    retry: RetryPolicy,
+    limiter: Option<RateLimiter>,
+    in_flight: Option<Semaphore>,
+    cache: Option<Cache>,
+}
+
+impl Http {
+    pub(crate) fn new(
+        client: HttpClient,
+        auth: Option<HeaderValue>,
+        retry: RetryPolicy,
+        limiter: Option<RateLimiter>,
+        in_flight: Option<Semaphore>,
+        cache: Option<Cache>,
+    ) -> Self {
+        Http { client, auth, retry, limiter, in_flight, cache }
+    }
+
+    /// Whether requests carry an API key.
+    pub fn has_api_key(&self) -> bool {
+        self.auth.is_some()
+    }
+
+    /// Fail early for endpoints that cannot be called anonymously.
+    pub(crate) fn require_api_key(&self) -> Result<(), OctopustError> {
+        if self.has_api_key() {
+            Ok(())
+        } else {
+            Err(OctopustError::MissingApiKey)
+        }
+    }
+
+    /// Start a GET request with authentication applied.
+    pub(crate) fn get(&self, url: &str) -> RequestBuilder {
+        let req = self.client.get(url);
+        match &self.auth {
+            Some(auth) => req.header(header::AUTHORIZATION, auth.clone()),
+            None => req,
+        }
+    }
+
+    /// Start a POST request without the REST API's Basic authentication;
+    /// used for GraphQL, which authenticates with a JWT instead.
+    pub(crate) fn post(&self, url: &str) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    /// Send a request and read the whole body, answering GET requests from
+    /// the cache when one is configured.
+    pub(crate) async fn send(&self, req: RequestBuilder) -> Result<RawResponse, reqwest::Error> {
+        let mut request = req.build()?;
+        let lookup = match &self.cache {
+            Some(cache) if request.method() == Method::GET => cache.lookup(request.url()).map(|l| (cache, l)),
+            _ => None,
+        };
+        let Some((cache, lookup)) = lookup else {
+            return self.send_with_retry(request).await;
+        };
+
+        if let Some(resp) = lookup.fresh() {
+            return Ok(resp);
+        }
+        request.headers_mut().extend(lookup.conditional_headers());
+        let resp = self.send_with_retry(request).await?;
+        Ok(lookup.complete(cache, resp))
+    }
+
+    /// Send a request, retrying transient failures of idempotent requests
+    /// according to the configured `RetryPolicy`.
+    async fn send_with_retry(&self, request: Request) -> Result<RawResponse, reqwest::Error> {
+        let idempotent = matches!(*request.method(), Method::GET | Method::HEAD);
+        let mut attempt = 1;
+
+        loop {
+            let retry = if idempotent && attempt < self.retry.max_attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+            let Some(this_attempt) = retry else {
+                return self.execute(request).await;
+            };
+
+            let delay = match self.execute(this_attempt).await {
+                Ok(resp) if RetryPolicy::is_retryable_status(resp.status) => {
+                    match self.retry.delay(attempt, Some(&resp.headers)) {
+                        Some(delay) => delay,
+                        None => return Ok(resp),
+                    }
+                }
+                Err(e) if RetryPolicy::is_retryable_error(&e) => {
+                    match self.retry.delay(attempt, None) {
+                        Some(delay) => delay,
+                        None => return Err(e),
+                    }
+                }
+                result => return result,
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// A single attempt: wait for the rate limiter, then hold an in-flight
+    /// slot until the body has been read.
+    async fn execute(&self, request: Request) -> Result<RawResponse, reqwest::Error> {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire().await;
+        }
+        let _permit = match &self.in_flight {
+            Some(semaphore) => Some(semaphore.acquire().await.expect("in-flight semaphore is never closed")),
+            None => None,
+        };
+
+        let resp = self.client.execute(request).await?;
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let body = resp.bytes().await?;
+        Ok(RawResponse { status, headers, body })
+    }
+}
+
+/// A fully-read HTTP response.
+#[derive(Debug)]
+pub(crate) struct RawResponse {
+    pub(crate) status: StatusCode,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Bytes,
+}
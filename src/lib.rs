@@ -15,10 +15,33 @@
 //! }
 //! ```
 
+pub mod cache;
 pub mod client;
+#[cfg(feature = "chrono")]
+pub mod compare;
 pub mod error;
+pub mod export;
 pub mod models;
-pub mod api;
+#[cfg(feature = "chrono")]
+pub mod agile;
+pub(crate) mod api;
+#[cfg(feature = "chrono")]
+pub mod billing;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod graphql;
+pub(crate) mod http;
+pub mod pagination;
+pub mod rate_limit;
+pub mod region;
+pub mod retry;
+#[cfg(feature = "store")]
+pub mod store;
+pub mod tariff_code;
+#[cfg(feature = "testing")]
+pub mod testing;
 
-pub use client::Client;
-pub use error::OctopustError;
\ No newline at end of file
+pub use client::{Client, ClientBuilder};
+pub use error::OctopustError;
+pub use region::Region;
+pub use tariff_code::TariffCode;
\ No newline at end of file
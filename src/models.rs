@@ -1,9 +1,36 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+
+/// Timestamp type used in responses.
+///
+/// With the `chrono` feature this is `DateTime<FixedOffset>`, keeping the
+/// offset the API sent (e.g. `+01:00` during BST); otherwise the raw
+/// ISO 8601 string.
+///
+/// Because of this, enabling `chrono` (directly or through `store`, `arrow`
+/// or `cli`) changes the type of every timestamp field for all users of the
+/// crate in the build. Code that must compile either way should only use
+/// these fields through `Display` or `Debug`.
+#[cfg(feature = "chrono")]
+pub type Timestamp = chrono::DateTime<chrono::FixedOffset>;
+#[cfg(not(feature = "chrono"))]
+pub type Timestamp = String;
+
+/// Timestamp type accepted by queries.
+///
+/// With the `chrono` feature this is `DateTime<Utc>`; otherwise an
+/// ISO 8601 string such as `"2024-01-01T00:00:00Z"`. Like [`Timestamp`],
+/// it changes for every user of the crate when any of them enables
+/// `chrono`.
+#[cfg(feature = "chrono")]
+pub type QueryTimestamp<'a> = chrono::DateTime<chrono::Utc>;
+#[cfg(not(feature = "chrono"))]
+pub type QueryTimestamp<'a> = &'a str;
 
 #[derive(Default)]
 pub struct ProductQuery<'a> {
-    pub available_at: Option<&'a str>,
+    pub available_at: Option<QueryTimestamp<'a>>,
     pub brand: Option<&'a str>,
     pub is_business: Option<&'a bool>,
     pub is_green: Option<&'a bool>,
@@ -16,27 +43,84 @@ pub struct ProductQuery<'a> {
 #[derive(Default)]
 pub struct RetrieveProductQuery<'a> {
     pub product_code: &'a str,
-    pub tariffs_active_at: Option<&'a str>,
+    pub tariffs_active_at: Option<QueryTimestamp<'a>>,
 }
 
 #[derive(Default)]
 pub struct ListElectrictyConsumptionQuery<'a> {
     pub mpan: &'a str,
-    pub group_by: Option<&'a str>,
+    pub group_by: Option<GroupBy>,
     pub serial_number: &'a str,
-    pub period_from: Option<&'a str>,
-    pub period_to: Option<&'a str>,
-    pub order_by: Option<&'a str>,
+    pub period_from: Option<QueryTimestamp<'a>>,
+    pub period_to: Option<QueryTimestamp<'a>>,
+    pub order_by: Option<Order>,
     pub page: Option<u32>,
     pub page_size: Option<u32>,
 }
 
+/// How consumption readings are aggregated (`group_by`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+}
+
+impl GroupBy {
+    /// The value sent to the API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupBy::Hour => "hour",
+            GroupBy::Day => "day",
+            GroupBy::Week => "week",
+            GroupBy::Month => "month",
+            GroupBy::Quarter => "quarter",
+        }
+    }
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Sort order for consumption readings (`order_by`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Order {
+    /// Oldest first.
+    #[serde(rename = "period")]
+    PeriodAsc,
+    /// Newest first; the API default.
+    #[serde(rename = "-period")]
+    PeriodDesc,
+}
+
+impl Order {
+    /// The value sent to the API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Order::PeriodAsc => "period",
+            Order::PeriodDesc => "-period",
+        }
+    }
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Default)]
 pub struct ListUnitRatesQuery<'a> {
     pub product_code: &'a str,
     pub tariff_code: &'a str,
-    pub period_from: Option<&'a str>,
-    pub period_to: Option<&'a str>,
+    pub period_from: Option<QueryTimestamp<'a>>,
+    pub period_to: Option<QueryTimestamp<'a>>,
     pub page: Option<u32>,
     pub page_size: Option<u32>,
 }
@@ -50,11 +134,11 @@ pub struct ListGridSupplyPointsQuery<'a> {
 #[derive(Default)]
 pub struct ListGasConsumptionQuery<'a> {
     pub mprn: &'a str,
-    pub group_by:  Option<&'a str>,
+    pub group_by: Option<GroupBy>,
     pub serial_number: &'a str,
-    pub period_from: Option<&'a str>,
-    pub period_to: Option<&'a str>,
-    pub order_by: Option<&'a str>,
+    pub period_from: Option<QueryTimestamp<'a>>,
+    pub period_to: Option<QueryTimestamp<'a>>,
+    pub order_by: Option<Order>,
     pub page: Option<u32>,
     pub page_size: Option<u32>,
 }
@@ -67,9 +151,57 @@ pub struct ProductsResponse {
     pub results: Vec<Product>,
 }
 
+/// Whether a product is for importing or exporting electricity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Direction {
+    Import,
+    Export,
+}
+
+impl Direction {
+    /// The value the API uses.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Import => "IMPORT",
+            Direction::Export => "EXPORT",
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a tariff charge is paid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PaymentMethod {
+    DirectDebit,
+    NonDirectDebit,
+}
+
+impl PaymentMethod {
+    /// The value the API uses.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentMethod::DirectDebit => "DIRECT_DEBIT",
+            PaymentMethod::NonDirectDebit => "NON_DIRECT_DEBIT",
+        }
+    }
+}
+
+impl fmt::Display for PaymentMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Product {
-    pub direction: String,
+    pub direction: Direction,
     pub code: String,
     pub full_name: String,
     pub display_name: String,
@@ -81,8 +213,8 @@ pub struct Product {
     pub is_business: bool,
     pub is_restricted: bool,
     pub term: Option<u32>,
-    pub available_from: Option<String>,
-    pub available_to: Option<String>,
+    pub available_from: Option<Timestamp>,
+    pub available_to: Option<Timestamp>,
     pub brand: Option<String>,
     pub links: Vec<Link>,
 }
@@ -98,7 +230,7 @@ pub struct Link {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProductDetail {
-    pub tariffs_active_at: Option<String>,
+    pub tariffs_active_at: Option<Timestamp>,
     pub code: String,
     pub full_name: String,
     pub display_name: String,
@@ -110,8 +242,8 @@ pub struct ProductDetail {
     pub is_business: bool,
     pub is_restricted: bool,
     pub term: Option<u32>,
-    pub available_from: Option<String>,
-    pub available_to: Option<String>,
+    pub available_from: Option<Timestamp>,
+    pub available_to: Option<Timestamp>,
     pub brand: Option<String>,
     pub links: Vec<Link>,
 
@@ -207,13 +339,13 @@ pub struct DualFuelDualRate {
 }
 
 /// Tariff Charges - day, night, standard, standing, etc
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TariffCharge {
     pub value_exc_vat: f64,
     pub value_inc_vat: f64,
-    pub valid_from: String,
-    pub valid_to: Option<String>,
-    pub payment_method: Option<String>,
+    pub valid_from: Timestamp,
+    pub valid_to: Option<Timestamp>,
+    pub payment_method: Option<PaymentMethod>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -225,11 +357,11 @@ pub struct TariffChargesResponse {
 }
 
 /// Consumption
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsumptionReading {
     pub consumption: f64,
-    pub interval_start: String,
-    pub interval_end: String,
+    pub interval_start: Timestamp,
+    pub interval_end: Timestamp,
 }
 
 #[derive(Debug, Deserialize)]
@@ -241,7 +373,7 @@ pub struct ConsumptionResponse {
 }
 
 // Supply points
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GridSupplyPoint {
     pub group_id: String,
 }
@@ -255,9 +387,90 @@ pub struct GridSupplyPointsResponse {
 }
 
 // Get electricity mpan
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MpanInfo {
     pub gsp: String,
     pub mpan: String,
     pub profile_class: u8,
+}
+
+// Accounts
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Account {
+    pub number: String,
+    pub properties: Vec<Property>,
+}
+
+impl Account {
+    /// Electricity meter points across every property on the account.
+    pub fn electricity_meter_points(&self) -> impl Iterator<Item = &ElectricityMeterPoint> {
+        self.properties.iter().flat_map(|p| &p.electricity_meter_points)
+    }
+
+    /// Gas meter points across every property on the account.
+    pub fn gas_meter_points(&self) -> impl Iterator<Item = &GasMeterPoint> {
+        self.properties.iter().flat_map(|p| &p.gas_meter_points)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Property {
+    pub id: u64,
+    pub moved_in_at: Option<Timestamp>,
+    pub moved_out_at: Option<Timestamp>,
+    pub address_line_1: Option<String>,
+    pub address_line_2: Option<String>,
+    pub address_line_3: Option<String>,
+    pub town: Option<String>,
+    pub county: Option<String>,
+    pub postcode: Option<String>,
+    #[serde(default)]
+    pub electricity_meter_points: Vec<ElectricityMeterPoint>,
+    #[serde(default)]
+    pub gas_meter_points: Vec<GasMeterPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElectricityMeterPoint {
+    pub mpan: String,
+    pub profile_class: Option<u8>,
+    pub consumption_standard: Option<f64>,
+    #[serde(default)]
+    pub meters: Vec<Meter>,
+    #[serde(default)]
+    pub agreements: Vec<Agreement>,
+    #[serde(default)]
+    pub is_export: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GasMeterPoint {
+    pub mprn: String,
+    pub consumption_standard: Option<f64>,
+    #[serde(default)]
+    pub meters: Vec<Meter>,
+    #[serde(default)]
+    pub agreements: Vec<Agreement>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Meter {
+    pub serial_number: String,
+    #[serde(default)]
+    pub registers: Vec<MeterRegister>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeterRegister {
+    pub identifier: String,
+    pub rate: Option<String>,
+    pub is_settlement_register: Option<bool>,
+}
+
+/// A period during which a meter point was on a given tariff.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Agreement {
+    pub tariff_code: String,
+    pub valid_from: Timestamp,
+    pub valid_to: Option<Timestamp>,
 }
\ No newline at end of file
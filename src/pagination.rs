@@ -0,0 +1,114 @@
+//! Automatic pagination over the list endpoints.
+//!
+//! Every list endpoint returns a page of `results` plus a `next` URL. The
+//! `Client::stream_*` methods wrap that in a [`Stream`] of items that keeps
+//! following `next` until it runs out or a [`PaginationLimits`] is reached.
+
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::api;
+use crate::error::OctopustError;
+use crate::http::Http;
+use crate::models::{
+    ConsumptionReading, ConsumptionResponse, GridSupplyPoint, GridSupplyPointsResponse, Product,
+    ProductsResponse, TariffCharge, TariffChargesResponse,
+};
+
+/// Optional caps on how much a pagination stream will fetch.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaginationLimits {
+    /// Stop after yielding this many items.
+    pub max_items: Option<usize>,
+    /// Stop after fetching this many pages.
+    pub max_pages: Option<usize>,
+}
+
+/// A single page of a paginated list response.
+pub trait Paginated: DeserializeOwned {
+    type Item;
+
+    /// URL of the next page, if there is one.
+    fn next_page(&self) -> Option<&str>;
+
+    /// Consume the page, returning its items.
+    fn into_results(self) -> Vec<Self::Item>;
+}
+
+macro_rules! impl_paginated {
+    ($response:ty, $item:ty) => {
+        impl Paginated for $response {
+            type Item = $item;
+
+            fn next_page(&self) -> Option<&str> {
+                self.next.as_deref()
+            }
+
+            fn into_results(self) -> Vec<$item> {
+                self.results
+            }
+        }
+    };
+}
+
+impl_paginated!(ProductsResponse, Product);
+impl_paginated!(TariffChargesResponse, TariffCharge);
+impl_paginated!(ConsumptionResponse, ConsumptionReading);
+impl_paginated!(GridSupplyPointsResponse, GridSupplyPoint);
+
+struct PageState {
+    url: Option<Result<String, OctopustError>>,
+    params: Vec<(&'static str, String)>,
+    pages_fetched: usize,
+}
+
+/// Stream every item from `url`, following `next` links.
+///
+/// An `Err` for `url` (e.g. invalid query input) is yielded as the only
+/// item. `params` are only sent with the first request; the `next` URLs returned
+/// by the API already carry the full query string. When `requires_api_key`
+/// is set and the client is anonymous, the stream yields a single
+/// `OctopustError::MissingApiKey`.
+pub(crate) fn paginate<'a, R>(
+    http: &'a Http,
+    url: Result<String, OctopustError>,
+    params: Vec<(&'static str, String)>,
+    limits: PaginationLimits,
+    requires_api_key: bool,
+    what: &'static str,
+) -> impl Stream<Item = Result<R::Item, OctopustError>> + 'a
+where
+    R: Paginated + 'a,
+    R::Item: 'a,
+{
+    let state = PageState {
+        url: Some(url),
+        params,
+        pages_fetched: 0,
+    };
+
+    let pages = stream::try_unfold(state, move |mut state| async move {
+        let url = match state.url.take() {
+            Some(url) => url?,
+            None => return Ok(None),
+        };
+        if limits.max_pages.is_some_and(|max| state.pages_fetched >= max) {
+            return Ok(None);
+        }
+
+        if requires_api_key {
+            http.require_api_key()?;
+        }
+
+        let params = std::mem::take(&mut state.params);
+        let page: R = api::get_json(http, &url, &params, what).await?;
+        state.pages_fetched += 1;
+        state.url = page.next_page().map(|next| Ok(next.to_string()));
+        Ok::<_, OctopustError>(Some((page.into_results(), state)))
+    });
+
+    pages
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+        .take(limits.max_items.unwrap_or(usize::MAX))
+}
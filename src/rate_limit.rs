@@ -0,0 +1,93 @@
+//! Client-side rate limiting shared by every clone of a `Client`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// A token-bucket limit on how often requests are sent.
+///
+/// The bucket holds up to `burst` tokens and refills at `requests` per
+/// `per`. Each HTTP attempt, including retries, takes one token.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    /// Number of requests allowed per `per`.
+    pub requests: u32,
+    /// The window `requests` is measured over.
+    pub per: Duration,
+    /// How many requests may be sent back-to-back after a quiet period.
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Allow `requests` per second, with a burst of the same size.
+    pub fn per_second(requests: u32) -> Self {
+        RateLimit {
+            requests,
+            per: Duration::from_secs(1),
+            burst: requests,
+        }
+    }
+
+    /// Allow `requests` per minute, with a burst of the same size.
+    pub fn per_minute(requests: u32) -> Self {
+        RateLimit {
+            requests,
+            per: Duration::from_secs(60),
+            burst: requests,
+        }
+    }
+
+    /// Override the burst size.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        let capacity = f64::from(limit.burst.max(1));
+        RateLimiter {
+            capacity,
+            refill_per_sec: f64::from(limit.requests) / limit.per.as_secs_f64(),
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a token is available and take it.
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+                bucket.updated = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_per_sec)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
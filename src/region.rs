@@ -0,0 +1,156 @@
+//! The 14 distribution network (DNO) regions of Great Britain.
+//!
+//! Regions appear as the last letter of a tariff code, as `_X` grid supply
+//! point group IDs, and as `_X` keys in a product's tariff lists.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::OctopustError;
+use crate::models::{GridSupplyPoint, MpanInfo};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Region {
+    EasternEngland,
+    EastMidlands,
+    London,
+    MerseysideAndNorthernWales,
+    WestMidlands,
+    NorthEasternEngland,
+    NorthWesternEngland,
+    SouthernEngland,
+    SouthEasternEngland,
+    SouthernWales,
+    SouthWesternEngland,
+    Yorkshire,
+    SouthernScotland,
+    NorthernScotland,
+}
+
+impl Region {
+    /// All regions, in letter order.
+    pub const ALL: [Region; 14] = [
+        Region::EasternEngland,
+        Region::EastMidlands,
+        Region::London,
+        Region::MerseysideAndNorthernWales,
+        Region::WestMidlands,
+        Region::NorthEasternEngland,
+        Region::NorthWesternEngland,
+        Region::SouthernEngland,
+        Region::SouthEasternEngland,
+        Region::SouthernWales,
+        Region::SouthWesternEngland,
+        Region::Yorkshire,
+        Region::SouthernScotland,
+        Region::NorthernScotland,
+    ];
+
+    /// The region letter, `A` to `P` (`I` and `O` are not used).
+    pub fn letter(&self) -> char {
+        match self {
+            Region::EasternEngland => 'A',
+            Region::EastMidlands => 'B',
+            Region::London => 'C',
+            Region::MerseysideAndNorthernWales => 'D',
+            Region::WestMidlands => 'E',
+            Region::NorthEasternEngland => 'F',
+            Region::NorthWesternEngland => 'G',
+            Region::SouthernEngland => 'H',
+            Region::SouthEasternEngland => 'J',
+            Region::SouthernWales => 'K',
+            Region::SouthWesternEngland => 'L',
+            Region::Yorkshire => 'M',
+            Region::SouthernScotland => 'N',
+            Region::NorthernScotland => 'P',
+        }
+    }
+
+    /// The grid supply point group ID, e.g. `_C` for London.
+    pub fn gsp_group_id(&self) -> String {
+        format!("_{}", self.letter())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Region::EasternEngland => "Eastern England",
+            Region::EastMidlands => "East Midlands",
+            Region::London => "London",
+            Region::MerseysideAndNorthernWales => "Merseyside and Northern Wales",
+            Region::WestMidlands => "West Midlands",
+            Region::NorthEasternEngland => "North Eastern England",
+            Region::NorthWesternEngland => "North Western England",
+            Region::SouthernEngland => "Southern England",
+            Region::SouthEasternEngland => "South Eastern England",
+            Region::SouthernWales => "Southern Wales",
+            Region::SouthWesternEngland => "South Western England",
+            Region::Yorkshire => "Yorkshire",
+            Region::SouthernScotland => "Southern Scotland",
+            Region::NorthernScotland => "Northern Scotland",
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Region> {
+        let letter = letter.to_ascii_uppercase();
+        Region::ALL.into_iter().find(|r| r.letter() == letter)
+    }
+}
+
+/// Accepts a letter (`C`) or a GSP group ID (`_C`), in either case.
+impl FromStr for Region {
+    type Err = OctopustError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let letter = trimmed.strip_prefix('_').unwrap_or(trimmed);
+        let mut chars = letter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Region::from_letter(c),
+            _ => None,
+        }
+        .ok_or_else(|| OctopustError::InvalidInput(format!("unknown region {s:?}")))
+    }
+}
+
+impl TryFrom<String> for Region {
+    type Error = OctopustError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Region> for String {
+    fn from(region: Region) -> Self {
+        region.gsp_group_id()
+    }
+}
+
+impl From<Region> for char {
+    fn from(region: Region) -> Self {
+        region.letter()
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl GridSupplyPoint {
+    /// The region for `group_id`, if it is a known one.
+    pub fn region(&self) -> Option<Region> {
+        self.group_id.parse().ok()
+    }
+}
+
+impl MpanInfo {
+    /// The region for `gsp`, if it is a known one.
+    pub fn region(&self) -> Option<Region> {
+        self.gsp.parse().ok()
+    }
+}
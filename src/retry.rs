@@ -0,0 +1,97 @@
+//! Retry policy for transient failures.
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::{Duration, SystemTime};
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+/// How the client retries idempotent requests that fail transiently.
+///
+/// A request is retried when it times out, fails to connect, or gets a
+/// 429, 502, 503 or 504 response. Only GET and HEAD requests are retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first. `1` disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each one after.
+    pub initial_backoff: Duration,
+    /// Upper bound on any single delay. A `Retry-After` longer than this
+    /// is not waited out and the response is returned as-is.
+    pub max_backoff: Duration,
+    /// Randomise each delay to avoid many clients retrying in lockstep.
+    pub jitter: bool,
+    /// Wait for the server's `Retry-After` header when present.
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn disabled() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+        matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT
+        )
+    }
+
+    pub(crate) fn is_retryable_error(err: &reqwest::Error) -> bool {
+        err.is_timeout() || err.is_connect()
+    }
+
+    /// Delay before retry number `retry` (starting at 1), or `None` if the
+    /// server asked for a longer wait than `max_backoff` allows.
+    pub(crate) fn delay(&self, retry: u32, headers: Option<&HeaderMap>) -> Option<Duration> {
+        if self.respect_retry_after {
+            if let Some(wait) = headers.and_then(retry_after) {
+                return (wait <= self.max_backoff).then_some(wait);
+            }
+        }
+
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+        if !self.jitter {
+            return Some(backoff);
+        }
+
+        // Equal jitter: keep half the delay, randomise the other half.
+        let half = backoff / 2;
+        let random = RandomState::new().hash_one(retry) as f64 / u64::MAX as f64;
+        Some(half + half.mul_f64(random))
+    }
+}
+
+/// Parse a `Retry-After` header given either as seconds or an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = httpdate::parse_http_date(value).ok()?;
+    Some(at.duration_since(SystemTime::now()).unwrap_or_default())
+}
@@ -0,0 +1,508 @@
+//! A local SQLite copy of consumption and tariff charges.
+//!
+//! [`Store`] keeps readings and charges in a SQLite database and syncs
+//! them incrementally: each meter and tariff remembers how far it has been
+//! fetched, and the next sync asks the API only for what came after that.
+//! Requires the `store` feature.
+//!
+//! ```no_run
+//! # async fn run(client: octopust::Client) -> Result<(), octopust::OctopustError> {
+//! use chrono::{TimeZone, Utc};
+//! use octopust::store::{MeterKey, Store};
+//!
+//! let store = Store::open("octopus.sqlite")?;
+//! let meter = MeterKey::electricity("2000000000000", "21L0000000");
+//! let since = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+//! let report = store.sync_consumption(&client, &meter, since).await?;
+//! println!("{} new readings", report.fetched);
+//! let readings = store.consumption(&meter, None, None)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, FixedOffset, Utc};
+use futures::{Stream, TryStreamExt};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use crate::client::Client;
+use crate::error::OctopustError;
+use crate::models::{
+    ConsumptionReading, ListElectrictyConsumptionQuery, ListGasConsumptionQuery, ListUnitRatesQuery, Order,
+    PaymentMethod, TariffCharge,
+};
+use crate::pagination::PaginationLimits;
+use crate::tariff_code::{Fuel, TariffCode};
+
+/// The largest page size the consumption endpoints accept.
+const CONSUMPTION_PAGE_SIZE: u32 = 25_000;
+
+/// The largest page size the tariff charge endpoints accept.
+const CHARGES_PAGE_SIZE: u32 = 1500;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS consumption (
+    fuel TEXT NOT NULL,
+    meter_point TEXT NOT NULL,
+    serial_number TEXT NOT NULL,
+    start_ts INTEGER NOT NULL,
+    interval_start TEXT NOT NULL,
+    interval_end TEXT NOT NULL,
+    consumption REAL NOT NULL,
+    PRIMARY KEY (fuel, meter_point, serial_number, start_ts)
+);
+CREATE TABLE IF NOT EXISTS tariff_charges (
+    tariff_code TEXT NOT NULL,
+    charge_type TEXT NOT NULL,
+    payment_method TEXT NOT NULL,
+    from_ts INTEGER NOT NULL,
+    valid_from TEXT NOT NULL,
+    valid_to TEXT,
+    value_exc_vat REAL NOT NULL,
+    value_inc_vat REAL NOT NULL,
+    PRIMARY KEY (tariff_code, charge_type, payment_method, from_ts)
+);
+CREATE TABLE IF NOT EXISTS consumption_checkpoints (
+    fuel TEXT NOT NULL,
+    meter_point TEXT NOT NULL,
+    serial_number TEXT NOT NULL,
+    synced_to TEXT NOT NULL,
+    PRIMARY KEY (fuel, meter_point, serial_number)
+);
+";
+
+/// A meter whose consumption is stored: the MPAN or MPRN plus the serial.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MeterKey {
+    pub fuel: Fuel,
+    /// The MPAN for electricity, or the MPRN for gas.
+    pub meter_point: String,
+    pub serial_number: String,
+}
+
+impl MeterKey {
+    pub fn electricity(mpan: &str, serial_number: &str) -> Self {
+        MeterKey {
+            fuel: Fuel::Electricity,
+            meter_point: mpan.to_string(),
+            serial_number: serial_number.to_string(),
+        }
+    }
+
+    pub fn gas(mprn: &str, serial_number: &str) -> Self {
+        MeterKey {
+            fuel: Fuel::Gas,
+            meter_point: mprn.to_string(),
+            serial_number: serial_number.to_string(),
+        }
+    }
+}
+
+/// Which charges of a tariff are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChargeType {
+    StandardUnitRates,
+    DayUnitRates,
+    NightUnitRates,
+    StandingCharges,
+}
+
+impl ChargeType {
+    /// The path segment used by the API, e.g. `standard-unit-rates`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChargeType::StandardUnitRates => "standard-unit-rates",
+            ChargeType::DayUnitRates => "day-unit-rates",
+            ChargeType::NightUnitRates => "night-unit-rates",
+            ChargeType::StandingCharges => "standing-charges",
+        }
+    }
+}
+
+/// What a sync fetched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncReport {
+    /// Readings or charges returned by the API and stored.
+    pub fetched: usize,
+    /// The `period_from` the next sync will ask for, or `None` if nothing
+    /// is stored yet. For consumption this is the end of the newest stored
+    /// reading. For tariff charges it is the start of the newest stored
+    /// charge, which is fetched again because its `valid_to` is filled in
+    /// once the next charge is published.
+    pub synced_to: Option<DateTime<FixedOffset>>,
+}
+
+/// A SQLite database of consumption and tariff charges.
+#[derive(Debug)]
+pub struct Store {
+    conn: Mutex<Connection>,
+}
+
+impl Store {
+    /// Open or create a database file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, OctopustError> {
+        Store::init(Connection::open(path)?)
+    }
+
+    /// A database that lives only as long as the `Store`.
+    pub fn open_in_memory() -> Result<Self, OctopustError> {
+        Store::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, OctopustError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Store { conn: Mutex::new(conn) })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().expect("store lock poisoned")
+    }
+
+    /// Insert readings, replacing any already stored for the same interval.
+    pub fn insert_consumption(&self, meter: &MeterKey, readings: &[ConsumptionReading]) -> Result<(), OctopustError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        insert_readings(&tx, meter, readings)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stored readings starting in `[from, to)`, oldest first.
+    pub fn consumption(
+        &self,
+        meter: &MeterKey,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<ConsumptionReading>, OctopustError> {
+        let conn = self.conn();
+        let mut query = conn.prepare_cached(
+            "SELECT interval_start, interval_end, consumption FROM consumption
+             WHERE fuel = ?1 AND meter_point = ?2 AND serial_number = ?3 AND start_ts >= ?4 AND start_ts < ?5
+             ORDER BY start_ts",
+        )?;
+        let rows = query.query_map(
+            params![
+                meter.fuel.prefix(),
+                meter.meter_point,
+                meter.serial_number,
+                from.map_or(i64::MIN, |t| t.timestamp()),
+                to.map_or(i64::MAX, |t| t.timestamp()),
+            ],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, f64>(2)?)),
+        )?;
+
+        let mut readings = Vec::new();
+        for row in rows {
+            let (start, end, consumption) = row?;
+            readings.push(ConsumptionReading {
+                consumption,
+                interval_start: parse_timestamp(&start)?,
+                interval_end: parse_timestamp(&end)?,
+            });
+        }
+        Ok(readings)
+    }
+
+    /// The end of the last reading fetched by [`sync_consumption`](Self::sync_consumption).
+    pub fn checkpoint(&self, meter: &MeterKey) -> Result<Option<DateTime<FixedOffset>>, OctopustError> {
+        let synced_to: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT synced_to FROM consumption_checkpoints
+                 WHERE fuel = ?1 AND meter_point = ?2 AND serial_number = ?3",
+                params![meter.fuel.prefix(), meter.meter_point, meter.serial_number],
+                |row| row.get(0),
+            )
+            .optional()?;
+        synced_to.as_deref().map(parse_timestamp).transpose()
+    }
+
+    /// Fetch readings newer than the checkpoint, or from `since` on the
+    /// first sync, and store them.
+    ///
+    /// Readings are stored a page at a time, each page in one transaction
+    /// with the checkpoint moved to the end of its newest reading. If a
+    /// page fails, the pages before it are kept and the next sync carries
+    /// on from there.
+    ///
+    /// Only intervals after the newest stored reading are asked for again.
+    /// A gap before it, such as a reading the meter sends late, is not
+    /// refetched; fetch that period from the [`Client`] and pass it to
+    /// [`insert_consumption`](Self::insert_consumption) to fill it.
+    pub async fn sync_consumption(
+        &self,
+        client: &Client,
+        meter: &MeterKey,
+        since: DateTime<Utc>,
+    ) -> Result<SyncReport, OctopustError> {
+        let period_from = self.checkpoint(meter)?.map_or(since, |t| t.with_timezone(&Utc));
+        let limits = PaginationLimits::default();
+        let fetched = match meter.fuel {
+            Fuel::Electricity => {
+                let query = ListElectrictyConsumptionQuery {
+                    mpan: &meter.meter_point,
+                    serial_number: &meter.serial_number,
+                    period_from: Some(period_from),
+                    order_by: Some(Order::PeriodAsc),
+                    page_size: Some(CONSUMPTION_PAGE_SIZE),
+                    ..Default::default()
+                };
+                self.store_pages(meter, client.stream_electricity_consumption(query, limits)).await?
+            }
+            Fuel::Gas => {
+                let query = ListGasConsumptionQuery {
+                    mprn: &meter.meter_point,
+                    serial_number: &meter.serial_number,
+                    period_from: Some(period_from),
+                    order_by: Some(Order::PeriodAsc),
+                    page_size: Some(CONSUMPTION_PAGE_SIZE),
+                    ..Default::default()
+                };
+                self.store_pages(meter, client.stream_gas_consumption(query, limits)).await?
+            }
+        };
+
+        Ok(SyncReport {
+            fetched,
+            synced_to: self.checkpoint(meter)?,
+        })
+    }
+
+    /// Store an oldest-first stream of readings a page at a time, keeping
+    /// the pages that arrived before any error.
+    async fn store_pages(
+        &self,
+        meter: &MeterKey,
+        readings: impl Stream<Item = Result<ConsumptionReading, OctopustError>>,
+    ) -> Result<usize, OctopustError> {
+        let mut readings = std::pin::pin!(readings);
+        let mut page = Vec::with_capacity(CONSUMPTION_PAGE_SIZE as usize);
+        let mut fetched = 0;
+        let end = loop {
+            match readings.try_next().await {
+                Ok(Some(reading)) => page.push(reading),
+                Ok(None) => break Ok(()),
+                Err(e) => break Err(e),
+            }
+            if page.len() == CONSUMPTION_PAGE_SIZE as usize {
+                self.commit_page(meter, &page)?;
+                fetched += page.len();
+                page.clear();
+            }
+        };
+        // Whatever is buffered came from pages that arrived in full.
+        self.commit_page(meter, &page)?;
+        end.map(|()| fetched + page.len())
+    }
+
+    /// Insert one page of readings and move the checkpoint past it.
+    fn commit_page(&self, meter: &MeterKey, readings: &[ConsumptionReading]) -> Result<(), OctopustError> {
+        let Some(latest) = readings.iter().map(|r| r.interval_end).max() else {
+            return Ok(());
+        };
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        insert_readings(&tx, meter, readings)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO consumption_checkpoints (fuel, meter_point, serial_number, synced_to)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![meter.fuel.prefix(), meter.meter_point, meter.serial_number, latest.to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Insert charges, replacing any already stored with the same start.
+    pub fn insert_tariff_charges(
+        &self,
+        tariff: &TariffCode,
+        charge_type: ChargeType,
+        charges: &[TariffCharge],
+    ) -> Result<(), OctopustError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT OR REPLACE INTO tariff_charges
+                 (tariff_code, charge_type, payment_method, from_ts, valid_from, valid_to, value_exc_vat, value_inc_vat)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for charge in charges {
+                insert.execute(params![
+                    tariff.as_str(),
+                    charge_type.as_str(),
+                    payment_method_str(charge.payment_method),
+                    charge.valid_from.timestamp(),
+                    charge.valid_from.to_rfc3339(),
+                    charge.valid_to.map(|t| t.to_rfc3339()),
+                    charge.value_exc_vat,
+                    charge.value_inc_vat,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stored charges in force at any point in `[from, to)`, oldest first.
+    pub fn tariff_charges(
+        &self,
+        tariff: &TariffCode,
+        charge_type: ChargeType,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<TariffCharge>, OctopustError> {
+        let conn = self.conn();
+        let mut query = conn.prepare_cached(
+            "SELECT payment_method, valid_from, valid_to, value_exc_vat, value_inc_vat FROM tariff_charges
+             WHERE tariff_code = ?1 AND charge_type = ?2 AND from_ts < ?3
+             ORDER BY from_ts, payment_method",
+        )?;
+        let rows = query.query_map(
+            params![tariff.as_str(), charge_type.as_str(), to.map_or(i64::MAX, |t| t.timestamp())],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, f64>(4)?,
+                ))
+            },
+        )?;
+
+        let mut charges = Vec::new();
+        for row in rows {
+            let (payment_method, valid_from, valid_to, value_exc_vat, value_inc_vat) = row?;
+            let valid_to = valid_to.as_deref().map(parse_timestamp).transpose()?;
+            if let (Some(from), Some(valid_to)) = (from, valid_to) {
+                if valid_to <= from {
+                    continue;
+                }
+            }
+            charges.push(TariffCharge {
+                value_exc_vat,
+                value_inc_vat,
+                valid_from: parse_timestamp(&valid_from)?,
+                valid_to,
+                payment_method: match payment_method.as_str() {
+                    "DIRECT_DEBIT" => Some(PaymentMethod::DirectDebit),
+                    "NON_DIRECT_DEBIT" => Some(PaymentMethod::NonDirectDebit),
+                    _ => None,
+                },
+            });
+        }
+        Ok(charges)
+    }
+
+    /// Fetch charges starting at or after the newest one stored, or from
+    /// `since` on the first sync, and store them.
+    ///
+    /// The newest stored charge is fetched again because its `valid_to`
+    /// is filled in once the next charge is published.
+    pub async fn sync_tariff_charges(
+        &self,
+        client: &Client,
+        tariff: &TariffCode,
+        charge_type: ChargeType,
+        since: DateTime<Utc>,
+    ) -> Result<SyncReport, OctopustError> {
+        let period_from = self
+            .newest_charge(tariff, charge_type)?
+            .map_or(since, |t| t.with_timezone(&Utc));
+
+        let query = ListUnitRatesQuery {
+            period_from: Some(period_from),
+            page_size: Some(CHARGES_PAGE_SIZE),
+            ..ListUnitRatesQuery::for_tariff(tariff)
+        };
+        let limits = PaginationLimits::default();
+        let charges: Vec<TariffCharge> = match (tariff.fuel(), charge_type) {
+            (Fuel::Electricity, ChargeType::StandardUnitRates) => {
+                collect(client.stream_electricity_tariff_standard_unit_rates(query, limits)).await?
+            }
+            (Fuel::Electricity, ChargeType::DayUnitRates) => {
+                collect(client.stream_electricity_tariff_day_unit_rates(query, limits)).await?
+            }
+            (Fuel::Electricity, ChargeType::NightUnitRates) => {
+                collect(client.stream_electricity_tariff_night_unit_rates(query, limits)).await?
+            }
+            (Fuel::Electricity, ChargeType::StandingCharges) => {
+                collect(client.stream_electricity_tariff_standing_charges(query, limits)).await?
+            }
+            (Fuel::Gas, ChargeType::StandardUnitRates) => {
+                collect(client.stream_gas_tariff_standard_unit_rates(query, limits)).await?
+            }
+            (Fuel::Gas, ChargeType::StandingCharges) => {
+                collect(client.stream_gas_tariff_standing_charges(query, limits)).await?
+            }
+            (Fuel::Gas, ChargeType::DayUnitRates | ChargeType::NightUnitRates) => {
+                return Err(OctopustError::InvalidInput("gas tariffs have no day or night rates".to_string()));
+            }
+        };
+
+        self.insert_tariff_charges(tariff, charge_type, &charges)?;
+        Ok(SyncReport {
+            fetched: charges.len(),
+            synced_to: self.newest_charge(tariff, charge_type)?,
+        })
+    }
+
+    /// The `valid_from` of the newest stored charge.
+    fn newest_charge(
+        &self,
+        tariff: &TariffCode,
+        charge_type: ChargeType,
+    ) -> Result<Option<DateTime<FixedOffset>>, OctopustError> {
+        let latest: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT valid_from FROM tariff_charges WHERE tariff_code = ?1 AND charge_type = ?2
+                 ORDER BY from_ts DESC LIMIT 1",
+                params![tariff.as_str(), charge_type.as_str()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        latest.as_deref().map(parse_timestamp).transpose()
+    }
+}
+
+fn insert_readings(
+    tx: &Transaction<'_>,
+    meter: &MeterKey,
+    readings: &[ConsumptionReading],
+) -> Result<(), OctopustError> {
+    let mut insert = tx.prepare_cached(
+        "INSERT OR REPLACE INTO consumption
+         (fuel, meter_point, serial_number, start_ts, interval_start, interval_end, consumption)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for reading in readings {
+        insert.execute(params![
+            meter.fuel.prefix(),
+            meter.meter_point,
+            meter.serial_number,
+            reading.interval_start.timestamp(),
+            reading.interval_start.to_rfc3339(),
+            reading.interval_end.to_rfc3339(),
+            reading.consumption,
+        ])?;
+    }
+    Ok(())
+}
+
+async fn collect(
+    stream: impl Stream<Item = Result<TariffCharge, OctopustError>>,
+) -> Result<Vec<TariffCharge>, OctopustError> {
+    stream.try_collect().await
+}
+
+fn payment_method_str(method: Option<PaymentMethod>) -> &'static str {
+    method.as_ref().map_or("", PaymentMethod::as_str)
+}
+
+fn parse_timestamp(s: &str) -> Result<DateTime<FixedOffset>, OctopustError> {
+    DateTime::parse_from_rfc3339(s).map_err(|e| OctopustError::InvalidInput(format!("bad timestamp {s:?} in store: {e}")))
+}
@@ -0,0 +1,224 @@
+//! Parsing and building tariff codes.
+//!
+//! A tariff code such as `E-2R-VAR-22-11-01-J` is made of the fuel (`E` or
+//! `G`), the register count (`1R` or `2R`), the product code and the
+//! region letter.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::OctopustError;
+use crate::models::{ListUnitRatesQuery, ProductDetail};
+use crate::region::Region;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fuel {
+    Electricity,
+    Gas,
+}
+
+impl Fuel {
+    /// The tariff code prefix, `E` or `G`.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Fuel::Electricity => "E",
+            Fuel::Gas => "G",
+        }
+    }
+}
+
+/// Single-rate (`1R`) or day/night (`2R`, e.g. Economy 7) metering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegisterType {
+    Single,
+    Dual,
+}
+
+impl RegisterType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegisterType::Single => "1R",
+            RegisterType::Dual => "2R",
+        }
+    }
+}
+
+/// A validated tariff code.
+///
+/// ```
+/// use octopust::tariff_code::{Fuel, RegisterType, TariffCode};
+/// use octopust::Region;
+///
+/// let code: TariffCode = "E-2R-VAR-22-11-01-J".parse().unwrap();
+/// assert_eq!(code.fuel(), Fuel::Electricity);
+/// assert_eq!(code.register_type(), RegisterType::Dual);
+/// assert_eq!(code.product_code(), "VAR-22-11-01");
+/// assert_eq!(code.region(), 'J');
+/// assert_eq!(code.dno_region(), Region::SouthEasternEngland);
+/// assert_eq!(code.to_string(), "E-2R-VAR-22-11-01-J");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TariffCode {
+    code: String,
+    fuel: Fuel,
+    register_type: RegisterType,
+    region: Region,
+}
+
+impl TariffCode {
+    /// Build a code from its parts. `region` is a region letter or a
+    /// [`Region`].
+    pub fn new(
+        fuel: Fuel,
+        register_type: RegisterType,
+        product_code: &str,
+        region: impl Into<char>,
+    ) -> Result<Self, OctopustError> {
+        format!("{}-{}-{}-{}", fuel.prefix(), register_type.as_str(), product_code, region.into()).parse()
+    }
+
+    /// The tariff code for `product` in `region`, as listed by the product.
+    ///
+    /// Fails if the product has no such tariff, so a dual-register code is
+    /// never built for a single-register product or vice versa. `product`
+    /// must come from `retrieve_product`, which lists the tariffs.
+    pub fn for_product(
+        product: &ProductDetail,
+        fuel: Fuel,
+        register_type: RegisterType,
+        region: impl Into<char>,
+    ) -> Result<Self, OctopustError> {
+        let region = region.into();
+        let key = format!("_{region}");
+        let tariff = match (fuel, register_type) {
+            (Fuel::Electricity, RegisterType::Single) => product.single_register_electricity_tariffs.as_ref()
+                .and_then(|tariffs| tariffs.get(&key))
+                .and_then(|t| t.direct_debit_monthly.as_ref().or(t.direct_debit_quarterly.as_ref())),
+            (Fuel::Electricity, RegisterType::Dual) => product.dual_register_electricity_tariffs.as_ref()
+                .and_then(|tariffs| tariffs.get(&key))
+                .and_then(|t| t.direct_debit_monthly.as_ref().or(t.direct_debit_quarterly.as_ref())),
+            (Fuel::Gas, RegisterType::Single) => product.single_register_gas_tariffs.as_ref()
+                .and_then(|tariffs| tariffs.get(&key))
+                .and_then(|t| t.direct_debit_monthly.as_ref().or(t.direct_debit_quarterly.as_ref())),
+            (Fuel::Gas, RegisterType::Dual) => {
+                return Err(OctopustError::InvalidInput("gas tariffs have a single register".to_string()))
+            }
+        };
+        let listed = tariff.and_then(|t| t.code.as_deref());
+
+        match listed {
+            Some(code) => code.parse(),
+            None => Err(OctopustError::InvalidInput(format!(
+                "product {} has no {}-{} tariff in region {region}",
+                product.code,
+                fuel.prefix(),
+                register_type.as_str()
+            ))),
+        }
+    }
+
+    pub fn fuel(&self) -> Fuel {
+        self.fuel
+    }
+
+    pub fn register_type(&self) -> RegisterType {
+        self.register_type
+    }
+
+    /// The product code, e.g. `VAR-22-11-01`.
+    pub fn product_code(&self) -> &str {
+        &self.code[5..self.code.len() - 2]
+    }
+
+    /// The region letter, `A` to `P`.
+    pub fn region(&self) -> char {
+        self.region.letter()
+    }
+
+    /// The region the tariff is for.
+    pub fn dno_region(&self) -> Region {
+        self.region
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.code
+    }
+}
+
+impl FromStr for TariffCode {
+    type Err = OctopustError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| OctopustError::InvalidInput(format!("invalid tariff code {s:?}: {reason}"));
+
+        let fuel = match s.get(..2) {
+            Some("E-") => Fuel::Electricity,
+            Some("G-") => Fuel::Gas,
+            _ => return Err(invalid("expected it to start with E- or G-")),
+        };
+        let register_type = match s.get(2..5) {
+            Some("1R-") => RegisterType::Single,
+            Some("2R-") => RegisterType::Dual,
+            _ => return Err(invalid("expected a 1R or 2R register count")),
+        };
+        if fuel == Fuel::Gas && register_type == RegisterType::Dual {
+            return Err(invalid("gas tariffs have a single register"));
+        }
+
+        let rest = &s[5..];
+        let (product_code, region) = match rest.rsplit_once('-') {
+            Some((product, region)) if !product.is_empty() => (product, region),
+            _ => return Err(invalid("expected a product code and region letter")),
+        };
+        let region = match region.chars().collect::<Vec<_>>()[..] {
+            [c] if c.is_ascii_uppercase() => Region::from_letter(c),
+            _ => None,
+        };
+        let region = region.ok_or_else(|| invalid("expected a region letter A to P"))?;
+        if !product_code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(invalid("the product code may only contain letters, digits and dashes"));
+        }
+
+        Ok(TariffCode {
+            code: s.to_string(),
+            fuel,
+            register_type,
+            region,
+        })
+    }
+}
+
+impl TryFrom<String> for TariffCode {
+    type Error = OctopustError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TariffCode> for String {
+    fn from(code: TariffCode) -> Self {
+        code.code
+    }
+}
+
+impl fmt::Display for TariffCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.code)
+    }
+}
+
+impl<'a> ListUnitRatesQuery<'a> {
+    /// A query for the charges of `tariff`, with the product code taken
+    /// from the tariff code.
+    pub fn for_tariff(tariff: &'a TariffCode) -> Self {
+        ListUnitRatesQuery {
+            product_code: tariff.product_code(),
+            tariff_code: tariff.as_str(),
+            ..Default::default()
+        }
+    }
+}
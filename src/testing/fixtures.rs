@@ -0,0 +1,415 @@
+//! Canned products, tariff charges, consumption, electricity meter points,
+//! accounts and grid supply points.
+//!
+//! [`Fixtures::canned`] covers one week, [`FIXTURE_START`] to
+//! [`FIXTURE_END`], in every region:
+//!
+//! - [`AGILE_PRODUCT`]: single-register electricity with half-hourly rates.
+//! - [`FLEXIBLE_PRODUCT`]: single- and dual-register electricity and gas,
+//!   with a price change on 2024-01-04 and separate Direct Debit and
+//!   non-Direct Debit charges.
+//! - [`FIXED_PRODUCT`]: a 12-month fixed tariff for electricity and gas.
+//!
+//! Half-hourly consumption is provided for one electricity and one gas
+//! meter, both on the London account [`ACCOUNT_NUMBER`], and postcodes for
+//! a few well-known addresses.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::region::Region;
+
+pub const AGILE_PRODUCT: &str = "AGILE-24-10-01";
+pub const FLEXIBLE_PRODUCT: &str = "VAR-22-11-01";
+pub const FIXED_PRODUCT: &str = "OE-FIX-12M-24-01-01";
+
+pub const ELECTRICITY_MPAN: &str = "2000000000000";
+pub const ELECTRICITY_SERIAL: &str = "21L0000000";
+pub const GAS_MPRN: &str = "3000000000";
+pub const GAS_SERIAL: &str = "G4P00000000000";
+pub const ACCOUNT_NUMBER: &str = "A-1234ABCD";
+
+/// Start of the canned rates and consumption.
+pub const FIXTURE_START: &str = "2024-01-01T00:00:00Z";
+/// End of the canned consumption; the last rates are open-ended.
+pub const FIXTURE_END: &str = "2024-01-08T00:00:00Z";
+
+/// [`FIXTURE_START`] in seconds since the epoch.
+const START: i64 = 1_704_067_200;
+/// When the flexible tariff's unit rates change.
+const PRICE_CHANGE: i64 = START + 3 * 86_400;
+const HALF_HOURS: i64 = 7 * 48;
+const VAT: f64 = 1.05;
+
+/// The data a [`MockOctopus`](super::MockOctopus) serves.
+///
+/// Values are the JSON the API would return, so fixtures can be built
+/// from `serde_json::json!` or by serializing the crate's own models.
+/// Results are sorted and paginated by the server, so the order they are
+/// added in does not matter.
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    /// `GET /products/`.
+    pub products: Vec<Value>,
+    /// `GET /products/{code}/`, by product code.
+    pub product_details: BTreeMap<String, Value>,
+    /// Unit rates and standing charges, by tariff code and charge type as it
+    /// appears in the URL (`standard-unit-rates`, `day-unit-rates`,
+    /// `night-unit-rates` or `standing-charges`).
+    pub tariff_charges: BTreeMap<(String, String), Vec<Value>>,
+    /// Electricity readings, by MPAN and meter serial number.
+    pub electricity_consumption: BTreeMap<(String, String), Vec<Value>>,
+    /// Gas readings, by MPRN and meter serial number.
+    pub gas_consumption: BTreeMap<(String, String), Vec<Value>>,
+    /// `GET /electricity-meter-points/{mpan}/`, by MPAN.
+    pub electricity_meter_points: BTreeMap<String, Value>,
+    /// `GET /accounts/{number}/`, by account number.
+    pub accounts: BTreeMap<String, Value>,
+    /// GSP group IDs (`_A` to `_P`), by postcode. Postcodes are matched
+    /// ignoring case and spaces.
+    pub postcodes: BTreeMap<String, Vec<String>>,
+}
+
+impl Fixtures {
+    /// The canned data described in the [module docs](self).
+    pub fn canned() -> Self {
+        let mut fixtures = Fixtures::default();
+
+        let agile = Product {
+            code: AGILE_PRODUCT,
+            full_name: "Agile Octopus October 2024 v1",
+            display_name: "Agile Octopus",
+            is_variable: true,
+            is_green: true,
+            term: Some(12),
+        };
+        let flexible = Product {
+            code: FLEXIBLE_PRODUCT,
+            full_name: "Flexible Octopus November 2022 v1",
+            display_name: "Flexible Octopus",
+            is_variable: true,
+            is_green: false,
+            term: None,
+        };
+        let fixed = Product {
+            code: FIXED_PRODUCT,
+            full_name: "Octopus 12M Fixed January 2024 v1",
+            display_name: "Octopus 12M Fixed",
+            is_variable: false,
+            is_green: false,
+            term: Some(12),
+        };
+
+        for (index, region) in Region::ALL.into_iter().enumerate() {
+            // Small regional differences, as in the real price cap.
+            let offset = index as f64 * 0.2;
+            let letter = region.letter();
+
+            let tariff = format!("E-1R-{AGILE_PRODUCT}-{letter}");
+            let rates = (0..HALF_HOURS)
+                .map(|i| charge(agile_rate(i) + offset, START + i * 1800, Some(START + (i + 1) * 1800), None))
+                .collect();
+            fixtures.add_charges(&tariff, "standard-unit-rates", rates);
+            fixtures.add_charges(&tariff, "standing-charges", vec![charge(45.0 + offset, START, None, None)]);
+
+            let tariff = format!("E-1R-{FLEXIBLE_PRODUCT}-{letter}");
+            fixtures.add_charges(&tariff, "standard-unit-rates", price_change(27.26 + offset, 25.96 + offset));
+            fixtures.add_charges(&tariff, "standing-charges", per_payment_method(45.57 + offset, START, None));
+            let tariff = format!("E-2R-{FLEXIBLE_PRODUCT}-{letter}");
+            fixtures.add_charges(&tariff, "day-unit-rates", per_payment_method(30.1 + offset, START, None));
+            fixtures.add_charges(&tariff, "night-unit-rates", per_payment_method(14.2 + offset, START, None));
+            fixtures.add_charges(&tariff, "standing-charges", per_payment_method(45.57 + offset, START, None));
+            let tariff = format!("G-1R-{FLEXIBLE_PRODUCT}-{letter}");
+            fixtures.add_charges(&tariff, "standard-unit-rates", price_change(6.89 + offset / 10.0, 6.50 + offset / 10.0));
+            fixtures.add_charges(&tariff, "standing-charges", per_payment_method(29.11 + offset, START, None));
+
+            let tariff = format!("E-1R-{FIXED_PRODUCT}-{letter}");
+            fixtures.add_charges(&tariff, "standard-unit-rates", vec![charge(24.5 + offset, START, None, None)]);
+            fixtures.add_charges(&tariff, "standing-charges", vec![charge(43.0 + offset, START, None, None)]);
+            let tariff = format!("G-1R-{FIXED_PRODUCT}-{letter}");
+            fixtures.add_charges(&tariff, "standard-unit-rates", vec![charge(6.1 + offset / 10.0, START, None, None)]);
+            fixtures.add_charges(&tariff, "standing-charges", vec![charge(28.0 + offset, START, None, None)]);
+        }
+
+        fixtures.products = vec![agile.summary(), flexible.summary(), fixed.summary()];
+        fixtures.product_details.insert(AGILE_PRODUCT.to_string(), agile.detail(true, false, false));
+        fixtures.product_details.insert(FLEXIBLE_PRODUCT.to_string(), flexible.detail(true, true, true));
+        fixtures.product_details.insert(FIXED_PRODUCT.to_string(), fixed.detail(true, false, true));
+
+        let electricity = (0..HALF_HOURS).map(|i| reading(electricity_usage(i), i)).collect();
+        fixtures
+            .electricity_consumption
+            .insert((ELECTRICITY_MPAN.to_string(), ELECTRICITY_SERIAL.to_string()), electricity);
+        let gas = (0..HALF_HOURS).map(|i| reading(gas_usage(i), i)).collect();
+        fixtures.gas_consumption.insert((GAS_MPRN.to_string(), GAS_SERIAL.to_string()), gas);
+
+        fixtures.electricity_meter_points.insert(
+            ELECTRICITY_MPAN.to_string(),
+            json!({ "gsp": Region::London.gsp_group_id(), "mpan": ELECTRICITY_MPAN, "profile_class": 1 }),
+        );
+        fixtures.accounts.insert(ACCOUNT_NUMBER.to_string(), account());
+
+        for (postcode, region) in [
+            ("SW1A 1AA", Region::London),
+            ("M1 1AE", Region::NorthWesternEngland),
+            ("CF10 1EP", Region::SouthernWales),
+            ("EH1 1YZ", Region::SouthernScotland),
+        ] {
+            fixtures.postcodes.insert(postcode.to_string(), vec![region.gsp_group_id()]);
+        }
+        fixtures
+    }
+
+    fn add_charges(&mut self, tariff_code: &str, charge_type: &str, charges: Vec<Value>) {
+        self.tariff_charges.insert((tariff_code.to_string(), charge_type.to_string()), charges);
+    }
+}
+
+struct Product {
+    code: &'static str,
+    full_name: &'static str,
+    display_name: &'static str,
+    is_variable: bool,
+    is_green: bool,
+    term: Option<u32>,
+}
+
+impl Product {
+    fn summary(&self) -> Value {
+        json!({
+            "code": self.code,
+            "direction": "IMPORT",
+            "full_name": self.full_name,
+            "display_name": self.display_name,
+            "description": format!("{} from Octopus Energy.", self.display_name),
+            "is_variable": self.is_variable,
+            "is_green": self.is_green,
+            "is_tracker": false,
+            "is_prepay": false,
+            "is_business": false,
+            "is_restricted": false,
+            "term": self.term,
+            "available_from": "2023-12-01T00:00:00Z",
+            "available_to": null,
+            "brand": "OCTOPUS_ENERGY",
+            "links": [{
+                "href": format!("https://api.octopus.energy/v1/products/{}/", self.code),
+                "method": "GET",
+                "rel": "self"
+            }]
+        })
+    }
+
+    fn detail(&self, single: bool, dual: bool, gas: bool) -> Value {
+        let mut detail = self.summary();
+        let fields = detail.as_object_mut().expect("summary is an object");
+        fields.remove("direction");
+        fields.insert("tariffs_active_at".to_string(), json!(FIXTURE_START));
+        let by_region = |prefix: &str, rates: &[&str]| -> Value {
+            Region::ALL
+                .into_iter()
+                .map(|region| {
+                    let code = format!("{prefix}-{}-{}", self.code, region.letter());
+                    (region.gsp_group_id(), json!({ "direct_debit_monthly": tariff(self.code, &code, rates) }))
+                })
+                .collect::<serde_json::Map<_, _>>()
+                .into()
+        };
+        let electricity_rates = ["standard_unit_rates", "standing_charges"];
+        fields.insert(
+            "single_register_electricity_tariffs".to_string(),
+            if single { by_region("E-1R", &electricity_rates) } else { json!({}) },
+        );
+        fields.insert(
+            "dual_register_electricity_tariffs".to_string(),
+            if dual { by_region("E-2R", &["day_unit_rates", "night_unit_rates", "standing_charges"]) } else { json!({}) },
+        );
+        fields.insert(
+            "single_register_gas_tariffs".to_string(),
+            if gas { by_region("G-1R", &electricity_rates) } else { json!({}) },
+        );
+        fields.insert("sample_quotes".to_string(), json!({}));
+        fields.insert(
+            "sample_consumption".to_string(),
+            json!({
+                "electricity_single_rate": { "electricity_standard": 2700 },
+                "electricity_dual_rate": { "electricity_day": 2100, "electricity_night": 1400 },
+                "dual_fuel_single_rate": { "electricity_standard": 2700, "gas_standard": 11500 },
+                "dual_fuel_dual_rate": { "electricity_day": 2100, "electricity_night": 1400, "gas_standard": 11500 }
+            }),
+        );
+        detail
+    }
+}
+
+/// A London account with the canned meters, which left the fixed tariff at
+/// the price change: electricity for Agile, gas for Flexible.
+fn account() -> Value {
+    let letter = Region::London.letter();
+    let agreements = |fuel: &str, product: &str| {
+        json!([
+            {
+                "tariff_code": format!("{fuel}-1R-{FIXED_PRODUCT}-{letter}"),
+                "valid_from": format_utc(START),
+                "valid_to": format_utc(PRICE_CHANGE)
+            },
+            {
+                "tariff_code": format!("{fuel}-1R-{product}-{letter}"),
+                "valid_from": format_utc(PRICE_CHANGE),
+                "valid_to": null
+            }
+        ])
+    };
+    json!({
+        "number": ACCOUNT_NUMBER,
+        "properties": [{
+            "id": 1_000_001,
+            "moved_in_at": format_utc(START),
+            "moved_out_at": null,
+            "address_line_1": "1 Example Street",
+            "address_line_2": "",
+            "address_line_3": "",
+            "town": "LONDON",
+            "county": "",
+            "postcode": "SW1A 1AA",
+            "electricity_meter_points": [{
+                "mpan": ELECTRICITY_MPAN,
+                "profile_class": 1,
+                "consumption_standard": 2900,
+                "meters": [{
+                    "serial_number": ELECTRICITY_SERIAL,
+                    "registers": [{ "identifier": "1", "rate": "STANDARD", "is_settlement_register": true }]
+                }],
+                "agreements": agreements("E", AGILE_PRODUCT),
+                "is_export": false
+            }],
+            "gas_meter_points": [{
+                "mprn": GAS_MPRN,
+                "consumption_standard": 11500,
+                "meters": [{ "serial_number": GAS_SERIAL }],
+                "agreements": agreements("G", FLEXIBLE_PRODUCT)
+            }]
+        }]
+    })
+}
+
+/// A tariff entry of a product detail, linking to its charges.
+fn tariff(product_code: &str, tariff_code: &str, rates: &[&str]) -> Value {
+    let fuel = if tariff_code.starts_with('G') { "gas" } else { "electricity" };
+    let links: Vec<Value> = rates
+        .iter()
+        .map(|rel| {
+            json!({
+                "href": format!(
+                    "https://api.octopus.energy/v1/products/{product_code}/{fuel}-tariffs/{tariff_code}/{}/",
+                    rel.replace('_', "-")
+                ),
+                "method": "GET",
+                "rel": rel
+            })
+        })
+        .collect();
+    json!({
+        "code": tariff_code,
+        "online_discount_exc_vat": 0,
+        "online_discount_inc_vat": 0,
+        "dual_fuel_discount_exc_vat": 0,
+        "dual_fuel_discount_inc_vat": 0,
+        "exit_fees_exc_vat": 0,
+        "exit_fees_inc_vat": 0,
+        "exit_fees_type": "NONE",
+        "links": links
+    })
+}
+
+fn charge(exc_vat: f64, from: i64, to: Option<i64>, payment_method: Option<&str>) -> Value {
+    json!({
+        "value_exc_vat": round(exc_vat),
+        "value_inc_vat": round(exc_vat * VAT),
+        "valid_from": format_utc(from),
+        "valid_to": to.map(format_utc),
+        "payment_method": payment_method
+    })
+}
+
+/// The same charge for Direct Debit and, slightly dearer, for other
+/// payment methods.
+fn per_payment_method(exc_vat: f64, from: i64, to: Option<i64>) -> Vec<Value> {
+    vec![
+        charge(exc_vat, from, to, Some("DIRECT_DEBIT")),
+        charge(exc_vat * 1.05, from, to, Some("NON_DIRECT_DEBIT")),
+    ]
+}
+
+/// Unit rates that change from `before` to `after` at [`PRICE_CHANGE`].
+fn price_change(before: f64, after: f64) -> Vec<Value> {
+    let mut charges = per_payment_method(before, START, Some(PRICE_CHANGE));
+    charges.extend(per_payment_method(after, PRICE_CHANGE, None));
+    charges
+}
+
+/// Half-hourly Agile price: cheap overnight, dear in the evening peak.
+fn agile_rate(half_hour: i64) -> f64 {
+    let base = match (half_hour / 2) % 24 {
+        0..=5 => 9.0,
+        16..=18 => 32.0,
+        _ => 19.0,
+    };
+    base + ((half_hour * 37) % 10) as f64 * 0.35
+}
+
+fn electricity_usage(half_hour: i64) -> f64 {
+    let base = match (half_hour / 2) % 24 {
+        0..=5 => 0.12,
+        16..=20 => 0.45,
+        6..=15 => 0.22,
+        _ => 0.25,
+    };
+    base + ((half_hour * 13) % 7) as f64 * 0.01
+}
+
+fn gas_usage(half_hour: i64) -> f64 {
+    let base = match (half_hour / 2) % 24 {
+        6..=8 | 17..=21 => 0.9,
+        _ => 0.15,
+    };
+    base + ((half_hour * 11) % 5) as f64 * 0.02
+}
+
+fn reading(consumption: f64, half_hour: i64) -> Value {
+    let start = START + half_hour * 1800;
+    json!({
+        "consumption": round(consumption),
+        "interval_start": format_utc(start),
+        "interval_end": format_utc(start + 1800)
+    })
+}
+
+/// Round to four decimal places, as the API does.
+fn round(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
+}
+
+/// Format seconds since the epoch as `YYYY-MM-DDTHH:MM:SSZ`.
+fn format_utc(secs: i64) -> String {
+    let (days, secs) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
@@ -0,0 +1,505 @@
+//! A local mock of the Octopus REST API for tests.
+//!
+//! [`MockOctopus`] starts an HTTP server on a random local port that serves
+//! [`Fixtures`]: products, tariff charges, consumption, electricity meter
+//! points, accounts and grid supply points. It paginates, filters by period
+//! and checks the API key the way the real API does, so tests exercise URL
+//! construction and response parsing without credentials or network access.
+//! Faults such as 500s, rate limiting or malformed bodies can be injected
+//! with [`Fault`]. Requires the `testing` feature.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), octopust::OctopustError> {
+//! use futures::TryStreamExt;
+//! use octopust::models::ListUnitRatesQuery;
+//! use octopust::testing::{fixtures, MockOctopus};
+//!
+//! let mock = MockOctopus::start().await;
+//! let client = mock.client();
+//! let rates: Vec<_> = client
+//!     .stream_electricity_tariff_standard_unit_rates(
+//!         ListUnitRatesQuery {
+//!             product_code: fixtures::AGILE_PRODUCT,
+//!             tariff_code: "E-1R-AGILE-24-10-01-C",
+//!             ..Default::default()
+//!         },
+//!         Default::default(),
+//!     )
+//!     .try_collect()
+//!     .await?;
+//! assert_eq!(rates.len(), 336);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod fixtures;
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use base64::Engine;
+use reqwest::Url;
+use serde_json::{json, Value};
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use crate::cache::parse_rfc3339;
+use crate::client::{Client, ClientBuilder};
+use crate::region::Region;
+use crate::tariff_code::{Fuel, TariffCode};
+
+pub use fixtures::Fixtures;
+
+/// The API key [`MockOctopus`] accepts.
+pub const API_KEY: &str = "sk_test_octopust";
+
+/// Default and largest `page_size` of the tariff charge endpoints.
+const CHARGES_PAGE_SIZE: (usize, usize) = (100, 1500);
+/// Default and largest `page_size` of the consumption endpoints.
+const CONSUMPTION_PAGE_SIZE: (usize, usize) = (100, 25_000);
+/// The product and grid supply point lists do not take a `page_size`.
+const LIST_PAGE_SIZE: usize = 100;
+
+/// A running mock of the Octopus REST API.
+///
+/// The server shuts down when this is dropped.
+#[derive(Debug)]
+pub struct MockOctopus {
+    server: MockServer,
+    state: Arc<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    base_url: String,
+    fixtures: Fixtures,
+    faults: Mutex<Vec<Fault>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+/// A response to return instead of the real one.
+///
+/// ```
+/// use octopust::testing::Fault;
+///
+/// // Fail the next two product requests, then behave normally.
+/// let fault = Fault::status(503).on_path("/products/").times(2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fault {
+    status: u16,
+    body: String,
+    headers: Vec<(String, String)>,
+    path_prefix: Option<String>,
+    remaining: Option<usize>,
+}
+
+impl Fault {
+    /// Respond with `status` and a Django REST Framework style error body.
+    pub fn status(status: u16) -> Self {
+        Fault {
+            status,
+            body: json!({ "detail": "Injected fault." }).to_string(),
+            headers: Vec::new(),
+            path_prefix: None,
+            remaining: None,
+        }
+    }
+
+    /// Respond with 429 and a `Retry-After` header.
+    pub fn rate_limited(retry_after_secs: u64) -> Self {
+        Fault::status(429)
+            .body(json!({ "detail": "Request was throttled." }).to_string())
+            .header("Retry-After", retry_after_secs.to_string())
+    }
+
+    /// Respond with 200 and a body that is not valid JSON.
+    pub fn malformed_body() -> Self {
+        Fault::status(200).body("{\"count\": 1, \"results\": [")
+    }
+
+    /// Replace the response body.
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Add a response header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Only apply to requests whose path starts with `prefix`.
+    pub fn on_path(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Only apply to the next `n` matching requests; by default a fault
+    /// applies until [`MockOctopus::clear_faults`] is called.
+    pub fn times(mut self, n: usize) -> Self {
+        self.remaining = Some(n);
+        self
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        self.remaining != Some(0) && self.path_prefix.as_deref().is_none_or(|prefix| path.starts_with(prefix))
+    }
+
+    fn response(&self) -> ResponseTemplate {
+        let mut response = ResponseTemplate::new(self.status)
+            .set_body_raw(self.body.clone().into_bytes(), "application/json");
+        for (name, value) in &self.headers {
+            response = response.insert_header(name.as_str(), value.as_str());
+        }
+        response
+    }
+}
+
+/// A request the mock received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// The API key from the `Authorization` header, if there was one.
+    pub api_key: Option<String>,
+}
+
+impl RecordedRequest {
+    /// The value of a query parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+impl MockOctopus {
+    /// Start a server with [`Fixtures::canned`].
+    pub async fn start() -> Self {
+        MockOctopus::with_fixtures(Fixtures::canned()).await
+    }
+
+    /// Start a server with the given fixtures.
+    pub async fn with_fixtures(fixtures: Fixtures) -> Self {
+        let server = MockServer::start().await;
+        let state = Arc::new(State {
+            base_url: server.uri(),
+            fixtures,
+            faults: Mutex::new(Vec::new()),
+            requests: Mutex::new(Vec::new()),
+        });
+        Mock::given(any()).respond_with(Handler(state.clone())).mount(&server).await;
+        MockOctopus { server, state }
+    }
+
+    /// The base URL to point a client at.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// A client builder with the base URL and [`API_KEY`] already set.
+    pub fn client_builder(&self) -> ClientBuilder {
+        Client::builder().base_url(self.uri()).api_key(API_KEY)
+    }
+
+    /// A client authenticated with [`API_KEY`].
+    pub fn client(&self) -> Client {
+        self.client_builder().build().expect("mock client configuration is valid")
+    }
+
+    /// Return `fault` instead of the real response for matching requests.
+    /// Faults are checked in the order they were injected.
+    pub fn inject(&self, fault: Fault) {
+        lock(&self.state.faults).push(fault);
+    }
+
+    /// Remove all injected faults.
+    pub fn clear_faults(&self) {
+        lock(&self.state.faults).clear();
+    }
+
+    /// Every request received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        lock(&self.state.requests).clone()
+    }
+}
+
+/// A panic in a test thread must not hide later requests behind a
+/// poisoned lock.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+struct Handler(Arc<State>);
+
+impl Respond for Handler {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let state = &self.0;
+        let path = request.url.path().to_string();
+        let api_key = api_key(request);
+        lock(&state.requests).push(RecordedRequest {
+            method: request.method.to_string(),
+            path: path.clone(),
+            query: request.url.query_pairs().map(|(k, v)| (k.into_owned(), v.into_owned())).collect(),
+            api_key: api_key.clone(),
+        });
+
+        {
+            let mut faults = lock(&state.faults);
+            if let Some(fault) = faults.iter_mut().find(|f| f.matches(&path)) {
+                if let Some(remaining) = &mut fault.remaining {
+                    *remaining -= 1;
+                }
+                return fault.response();
+            }
+        }
+
+        if request.method.as_str() != "GET" {
+            return error(405, json!({ "detail": format!("Method \"{}\" not allowed.", request.method) }));
+        }
+        state.route(&request.url, api_key.as_deref())
+    }
+}
+
+/// The API key from a Basic `Authorization` header.
+fn api_key(request: &Request) -> Option<String> {
+    let header = request.headers.get("authorization")?.to_str().ok()?;
+    let encoded = header.strip_prefix("Basic ")?;
+    let decoded = base64::engine::general_purpose::STANDARD.decode(encoded).ok()?;
+    let credentials = String::from_utf8(decoded).ok()?;
+    let (user, _password) = credentials.split_once(':')?;
+    Some(user.to_string())
+}
+
+impl State {
+    fn route(&self, url: &Url, api_key: Option<&str>) -> ResponseTemplate {
+        let segments: Vec<&str> = url.path().split('/').filter(|s| !s.is_empty()).collect();
+        match segments.as_slice() {
+            ["products"] => self.products(url),
+            ["products", code] => match self.fixtures.product_details.get(*code) {
+                Some(detail) => ResponseTemplate::new(200).set_body_json(detail),
+                None => not_found(),
+            },
+            ["products", product, fuel_tariffs, tariff, charge_type] => {
+                self.tariff_charges(url, product, fuel_tariffs, tariff, charge_type)
+            }
+            ["electricity-meter-points", mpan] => {
+                self.authenticated(api_key, &self.fixtures.electricity_meter_points, mpan)
+            }
+            ["accounts", number] => self.authenticated(api_key, &self.fixtures.accounts, number),
+            ["electricity-meter-points", mpan, "meters", serial, "consumption"] => {
+                self.consumption(url, api_key, &self.fixtures.electricity_consumption, mpan, serial)
+            }
+            ["gas-meter-points", mprn, "meters", serial, "consumption"] => {
+                self.consumption(url, api_key, &self.fixtures.gas_consumption, mprn, serial)
+            }
+            ["industry", "grid-supply-points"] => self.grid_supply_points(url),
+            _ => not_found(),
+        }
+    }
+
+    fn products(&self, url: &Url) -> ResponseTemplate {
+        let mut products = self.fixtures.products.clone();
+        for flag in ["is_variable", "is_green", "is_tracker", "is_business", "is_prepay"] {
+            if let Some(value) = param(url, flag) {
+                let wanted = value == "true";
+                products.retain(|p| p[flag].as_bool() == Some(wanted));
+            }
+        }
+        if let Some(brand) = param(url, "brand") {
+            products.retain(|p| p["brand"].as_str() == Some(brand.as_str()));
+        }
+        self.paginate(url, products, (LIST_PAGE_SIZE, LIST_PAGE_SIZE))
+    }
+
+    fn tariff_charges(
+        &self,
+        url: &Url,
+        product: &str,
+        fuel_tariffs: &str,
+        tariff: &str,
+        charge_type: &str,
+    ) -> ResponseTemplate {
+        // The real API only serves a tariff under its own product and fuel.
+        let consistent = tariff.parse::<TariffCode>().is_ok_and(|code| {
+            let fuel = match code.fuel() {
+                Fuel::Electricity => "electricity-tariffs",
+                Fuel::Gas => "gas-tariffs",
+            };
+            code.product_code() == product && fuel_tariffs == fuel
+        });
+        let charges = match self.fixtures.tariff_charges.get(&(tariff.to_string(), charge_type.to_string())) {
+            Some(charges) if consistent => charges,
+            _ => return not_found(),
+        };
+        let (from, to) = match period(url) {
+            Ok(period) => period,
+            Err(name) => return error(400, json!({ name: ["Enter a valid date/time."] })),
+        };
+
+        let mut charges: Vec<Value> = charges
+            .iter()
+            .filter(|c| {
+                let valid_from = timestamp(&c["valid_from"]);
+                let valid_to = timestamp(&c["valid_to"]);
+                from.is_none_or(|from| valid_to.is_none_or(|t| t > from))
+                    && to.is_none_or(|to| valid_from.is_some_and(|f| f < to))
+            })
+            .cloned()
+            .collect();
+        charges.sort_by_key(|c| Reverse(timestamp(&c["valid_from"])));
+        self.paginate(url, charges, CHARGES_PAGE_SIZE)
+    }
+
+    /// The fixture stored under `key`, for a request that needs the API key.
+    fn authenticated(&self, api_key: Option<&str>, fixtures: &BTreeMap<String, Value>, key: &str) -> ResponseTemplate {
+        if let Some(response) = unauthorized(api_key) {
+            return response;
+        }
+        match fixtures.get(key) {
+            Some(fixture) => ResponseTemplate::new(200).set_body_json(fixture),
+            None => not_found(),
+        }
+    }
+
+    fn consumption(
+        &self,
+        url: &Url,
+        api_key: Option<&str>,
+        meters: &BTreeMap<(String, String), Vec<Value>>,
+        meter_point: &str,
+        serial_number: &str,
+    ) -> ResponseTemplate {
+        if let Some(response) = unauthorized(api_key) {
+            return response;
+        }
+        let Some(readings) = meters.get(&(meter_point.to_string(), serial_number.to_string())) else {
+            return not_found();
+        };
+        if param(url, "group_by").is_some() {
+            return error(400, json!({ "group_by": ["Grouping is not supported by the mock server."] }));
+        }
+        let (from, to) = match period(url) {
+            Ok(period) => period,
+            Err(name) => return error(400, json!({ name: ["Enter a valid date/time."] })),
+        };
+
+        let mut readings: Vec<Value> = readings
+            .iter()
+            .filter(|r| {
+                let start = timestamp(&r["interval_start"]);
+                from.is_none_or(|from| start.is_some_and(|s| s >= from))
+                    && to.is_none_or(|to| start.is_some_and(|s| s < to))
+            })
+            .cloned()
+            .collect();
+        match param(url, "order_by").as_deref() {
+            None | Some("-period") => readings.sort_by_key(|r| Reverse(timestamp(&r["interval_start"]))),
+            Some("period") => readings.sort_by_key(|r| timestamp(&r["interval_start"])),
+            Some(_) => return error(400, json!({ "order_by": ["Select a valid choice."] })),
+        }
+        self.paginate(url, readings, CONSUMPTION_PAGE_SIZE)
+    }
+
+    fn grid_supply_points(&self, url: &Url) -> ResponseTemplate {
+        let group_ids: Vec<String> = match param(url, "postcode") {
+            Some(postcode) => {
+                let wanted = normalize_postcode(&postcode);
+                self.fixtures
+                    .postcodes
+                    .iter()
+                    .filter(|(postcode, _)| normalize_postcode(postcode) == wanted)
+                    .flat_map(|(_, ids)| ids.iter().cloned())
+                    .collect()
+            }
+            None => Region::ALL.iter().map(|r| r.gsp_group_id()).collect(),
+        };
+        let results = group_ids.into_iter().map(|id| json!({ "group_id": id })).collect();
+        self.paginate(url, results, (LIST_PAGE_SIZE, LIST_PAGE_SIZE))
+    }
+
+    /// One page of `items`, with `next` and `previous` links that keep the
+    /// rest of the query string.
+    fn paginate(&self, url: &Url, items: Vec<Value>, (default_size, max_size): (usize, usize)) -> ResponseTemplate {
+        let page = match param(url, "page").map(|p| p.parse::<usize>()) {
+            None => 1,
+            Some(Ok(page)) if page >= 1 => page,
+            Some(_) => return invalid_page(),
+        };
+        let page_size = param(url, "page_size")
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&size| size >= 1)
+            .map_or(default_size, |size| size.min(max_size));
+
+        let count = items.len();
+        let start = (page - 1) * page_size;
+        if page > 1 && start >= count {
+            return invalid_page();
+        }
+        let end = (start + page_size).min(count);
+        ResponseTemplate::new(200).set_body_json(json!({
+            "count": count,
+            "next": (end < count).then(|| self.page_url(url, page + 1)),
+            "previous": (page > 1).then(|| self.page_url(url, page - 1)),
+            "results": &items[start..end],
+        }))
+    }
+
+    fn page_url(&self, url: &Url, page: usize) -> String {
+        let mut link = Url::parse(&self.base_url).expect("mock server URI is valid");
+        link.set_path(url.path());
+        let query: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(name, _)| name != "page")
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        link.query_pairs_mut().extend_pairs(query).append_pair("page", &page.to_string());
+        link.to_string()
+    }
+}
+
+/// A 401, unless the request carried [`API_KEY`].
+fn unauthorized(api_key: Option<&str>) -> Option<ResponseTemplate> {
+    match api_key {
+        None => Some(error(401, json!({ "detail": "Authentication credentials were not provided." }))),
+        Some(key) if key != API_KEY => Some(error(401, json!({ "detail": "Invalid API key." }))),
+        Some(_) => None,
+    }
+}
+
+fn param(url: &Url, name: &str) -> Option<String> {
+    url.query_pairs().find(|(n, _)| n == name).map(|(_, v)| v.into_owned())
+}
+
+/// The `period_from` and `period_to` parameters, or the name of the one
+/// that does not parse.
+fn period(url: &Url) -> Result<(Option<SystemTime>, Option<SystemTime>), &'static str> {
+    let parse = |name: &'static str| match param(url, name) {
+        None => Ok(None),
+        Some(value) => parse_rfc3339(&value).map(Some).ok_or(name),
+    };
+    Ok((parse("period_from")?, parse("period_to")?))
+}
+
+fn timestamp(value: &Value) -> Option<SystemTime> {
+    value.as_str().and_then(parse_rfc3339)
+}
+
+fn normalize_postcode(postcode: &str) -> String {
+    postcode.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase()
+}
+
+fn error(status: u16, body: Value) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(body)
+}
+
+fn not_found() -> ResponseTemplate {
+    error(404, json!({ "detail": "Not found." }))
+}
+
+fn invalid_page() -> ResponseTemplate {
+    error(404, json!({ "detail": "Invalid page." }))
+}
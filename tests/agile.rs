@@ -0,0 +1,109 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use octopust::agile::AgileRates;
+use octopust::models::TariffChargesResponse;
+
+fn t(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 6, 1, hour, minute, 0).unwrap()
+}
+
+/// Half-hourly rates from 00:00, listed newest first as the API does.
+fn agile(prices: &[f64]) -> TariffChargesResponse {
+    let mut results: Vec<_> = prices
+        .iter()
+        .enumerate()
+        .map(|(i, price)| {
+            let start = t(0, 0) + Duration::minutes(30 * i as i64);
+            serde_json::json!({
+                "value_exc_vat": price,
+                "value_inc_vat": price * 1.05,
+                "valid_from": start.to_rfc3339(),
+                "valid_to": (start + Duration::minutes(30)).to_rfc3339(),
+                "payment_method": null
+            })
+        })
+        .collect();
+    results.reverse();
+    serde_json::from_value(serde_json::json!({
+        "count": results.len(), "next": null, "previous": null, "results": results
+    }))
+    .unwrap()
+}
+
+fn starts(slots: &[octopust::agile::Slot]) -> Vec<DateTime<Utc>> {
+    slots.iter().map(|s| s.start).collect()
+}
+
+#[test]
+fn test_cheapest_slots_in_time_order() {
+    let rates = AgileRates::new(agile(&[20.0, 5.0, 30.0, -2.0, 15.0, 4.0]));
+
+    let cheapest = rates.cheapest_slots(t(0, 0), t(3, 0), 3);
+    assert_eq!(starts(&cheapest), vec![t(0, 30), t(1, 30), t(2, 30)]);
+
+    // Only slots wholly inside the window count.
+    let cheapest = rates.cheapest_slots(t(0, 15), t(2, 0), 10);
+    assert_eq!(starts(&cheapest), vec![t(0, 30), t(1, 0), t(1, 30)]);
+}
+
+#[test]
+fn test_cheapest_block() {
+    let rates = AgileRates::new(agile(&[20.0, 5.0, 30.0, -2.0, 15.0, 4.0]));
+
+    let block = rates.cheapest_block(t(0, 0), t(3, 0), 2).unwrap();
+    assert_eq!((block.start, block.end), (t(1, 30), t(2, 30)));
+    assert!((block.average_exc_vat() - 6.5).abs() < 1e-9);
+    assert!((block.cost.exc_vat - 13.0).abs() < 1e-9);
+
+    assert!(rates.cheapest_block(t(0, 0), t(3, 0), 7).is_none());
+    assert!(rates.cheapest_block(t(0, 0), t(3, 0), 0).is_none());
+}
+
+#[test]
+fn test_cheapest_start_for_profile_with_negative_prices() {
+    let rates = AgileRates::new(agile(&[10.0, -20.0, 30.0, 1.0, 1.0, 1.0]));
+
+    // Equal use: the flat 1p run wins.
+    let flat = rates.cheapest_start(t(0, 0), t(3, 0), &[1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(flat.start, t(1, 30));
+    let block = rates.cheapest_block(t(0, 0), t(3, 0), 3).unwrap();
+    assert_eq!(block.start, t(1, 30));
+
+    // A heavy first half-hour should land on the negative price.
+    let heavy = rates.cheapest_start(t(0, 0), t(3, 0), &[3.0, 0.5]).unwrap();
+    assert_eq!(heavy.start, t(0, 30));
+    assert!((heavy.cost.exc_vat - (-60.0 + 15.0)).abs() < 1e-9);
+}
+
+#[test]
+fn test_partial_data_and_gaps() {
+    let mut response = agile(&[10.0, 12.0, 8.0, 9.0]);
+    // Drop 01:00-01:30, leaving a gap.
+    response.results.retain(|r| r.valid_from.to_utc() != t(1, 0));
+    let rates = AgileRates::new(&response);
+
+    assert_eq!(rates.available_until(), Some(t(2, 0)));
+    assert!(rates.cheapest_block(t(0, 0), t(12, 0), 3).is_none());
+    let block = rates.cheapest_block(t(0, 0), t(12, 0), 2).unwrap();
+    assert_eq!(block.start, t(0, 0));
+}
+
+#[test]
+fn test_splits_longer_rates_into_half_hours() {
+    let response: TariffChargesResponse = serde_json::from_value(serde_json::json!({
+        "count": 2, "next": null, "previous": null,
+        "results": [
+            { "value_exc_vat": 8.0, "value_inc_vat": 8.4, "valid_from": "2024-06-01T00:30:00Z",
+              "valid_to": "2024-06-01T04:30:00Z", "payment_method": null },
+            { "value_exc_vat": 30.0, "value_inc_vat": 31.5, "valid_from": "2024-06-01T04:30:00Z",
+              "valid_to": null, "payment_method": null }
+        ]
+    }))
+    .unwrap();
+    let rates = AgileRates::new(&response);
+
+    assert_eq!(rates.slots().len(), 8);
+    let block = rates.cheapest_block(t(0, 0), t(6, 0), 8).unwrap();
+    assert_eq!((block.start, block.end), (t(0, 30), t(4, 30)));
+}
@@ -0,0 +1,66 @@
+use futures::StreamExt;
+use octopust::{
+    models::{ListElectrictyConsumptionQuery, ListGridSupplyPointsQuery},
+    pagination::PaginationLimits,
+    Client, OctopustError,
+};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+fn consumption_query() -> ListElectrictyConsumptionQuery<'static> {
+    ListElectrictyConsumptionQuery {
+        mpan: "1000000000000",
+        serial_number: "00A0000000",
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_anonymous_client_sends_no_authorization() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/industry/grid-supply-points/"))
+        .and(|req: &Request| !req.headers.contains_key("authorization"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 0,
+            "next": null,
+            "previous": null,
+            "results": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+    assert!(!client.has_api_key());
+    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        ..Default::default()
+    }).await;
+    assert!(result.is_ok(), "list_industry_grid_supply_points failed: {:?}", result);
+}
+
+#[tokio::test]
+async fn test_anonymous_client_rejects_consumption() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+    let result = client.list_electricity_consumption(consumption_query()).await;
+    assert!(matches!(result, Err(OctopustError::MissingApiKey)));
+
+    let mut stream = Box::pin(
+        client.stream_electricity_consumption(consumption_query(), PaginationLimits::default()),
+    );
+    assert!(matches!(stream.next().await, Some(Err(OctopustError::MissingApiKey))));
+    assert!(stream.next().await.is_none());
+}
+
+#[test]
+fn test_anonymous_constructor() {
+    assert!(!Client::anonymous().has_api_key());
+    assert!(Client::new("sk_test").has_api_key());
+}
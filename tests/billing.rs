@@ -0,0 +1,196 @@
+#![cfg(feature = "chrono")]
+
+use chrono::NaiveDate;
+use octopust::billing::{calculate_cost, BillingOptions, GAS_KWH_PER_M3};
+use octopust::models::{ConsumptionResponse, PaymentMethod, TariffChargesResponse};
+
+fn consumption(readings: &[(&str, &str, f64)]) -> ConsumptionResponse {
+    let results: Vec<_> = readings
+        .iter()
+        .map(|(start, end, kwh)| {
+            serde_json::json!({ "consumption": kwh, "interval_start": start, "interval_end": end })
+        })
+        .collect();
+    serde_json::from_value(serde_json::json!({
+        "count": results.len(), "next": null, "previous": null, "results": results
+    }))
+    .unwrap()
+}
+
+fn charges(charges: &[(f64, &str, Option<&str>, Option<&str>)]) -> TariffChargesResponse {
+    let results: Vec<_> = charges
+        .iter()
+        .map(|(value, from, to, method)| {
+            serde_json::json!({
+                "value_exc_vat": value,
+                "value_inc_vat": value * 1.05,
+                "valid_from": from,
+                "valid_to": to,
+                "payment_method": method
+            })
+        })
+        .collect();
+    serde_json::from_value(serde_json::json!({
+        "count": results.len(), "next": null, "previous": null, "results": results
+    }))
+    .unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+#[test]
+fn test_prices_half_hourly_rates_and_days() {
+    let usage = consumption(&[
+        ("2024-01-01T23:00:00Z", "2024-01-01T23:30:00Z", 1.0),
+        ("2024-01-01T23:30:00Z", "2024-01-02T00:00:00Z", 2.0),
+        ("2024-01-02T00:00:00Z", "2024-01-02T00:30:00Z", 0.5),
+    ]);
+    let rates = charges(&[
+        (20.0, "2024-01-01T23:00:00Z", Some("2024-01-01T23:30:00Z"), None),
+        (10.0, "2024-01-01T23:30:00Z", Some("2024-01-02T00:00:00Z"), None),
+        (-5.0, "2024-01-02T00:00:00Z", Some("2024-01-02T00:30:00Z"), None),
+    ]);
+    let standing = charges(&[(40.0, "2023-01-01T00:00:00Z", None, None)]);
+
+    let bill = calculate_cost(&usage, &rates, &standing, &BillingOptions::default());
+
+    assert!(bill.unpriced.is_empty());
+    let costs: Vec<f64> = bill.intervals.iter().map(|i| i.cost.exc_vat).collect();
+    assert_eq!(costs, vec![20.0, 20.0, -2.5]);
+    assert_close(bill.intervals[2].cost.inc_vat, -2.625);
+
+    assert_eq!(bill.days.len(), 2);
+    assert_eq!(bill.days[0].date, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+    assert_close(bill.days[0].consumption, 3.0);
+    assert_close(bill.days[0].total().exc_vat, 80.0);
+    assert_close(bill.days[1].total().exc_vat, 37.5);
+
+    assert_close(bill.consumption(), 3.5);
+    assert_close(bill.energy().exc_vat, 37.5);
+    assert_close(bill.standing_charge().exc_vat, 80.0);
+    assert_close(bill.total().exc_vat, 117.5);
+    assert_close(bill.total().inc_vat, 117.5 * 1.05);
+}
+
+#[test]
+fn test_splits_readings_across_rate_changes() {
+    let usage = consumption(&[("2024-01-01T10:00:00Z", "2024-01-01T11:00:00Z", 4.0)]);
+    let rates = charges(&[
+        (10.0, "2024-01-01T10:00:00Z", Some("2024-01-01T10:30:00Z"), None),
+        (30.0, "2024-01-01T10:30:00Z", Some("2024-01-01T11:00:00Z"), None),
+    ]);
+    let bill = calculate_cost(&usage, &rates, charges(&[]), &BillingOptions::default());
+
+    assert_close(bill.intervals[0].cost.exc_vat, 2.0 * 10.0 + 2.0 * 30.0);
+}
+
+#[test]
+fn test_prorates_standing_charge_changes_within_a_day() {
+    // Reported in BST; the charge changes at 06:00 local time.
+    let usage = consumption(&[("2024-06-01T00:00:00+01:00", "2024-06-01T00:30:00+01:00", 1.0)]);
+    let rates = charges(&[(20.0, "2024-01-01T00:00:00Z", None, None)]);
+    let standing = charges(&[
+        (40.0, "2024-01-01T00:00:00Z", Some("2024-06-01T05:00:00Z"), None),
+        (60.0, "2024-06-01T05:00:00Z", None, None),
+    ]);
+    let bill = calculate_cost(&usage, &rates, &standing, &BillingOptions::default());
+
+    assert_eq!(bill.days[0].date, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+    assert_close(bill.days[0].standing_charge.exc_vat, 40.0 * 0.25 + 60.0 * 0.75);
+}
+
+#[test]
+fn test_filters_by_payment_method() {
+    let usage = consumption(&[("2024-01-01T10:00:00Z", "2024-01-01T10:30:00Z", 1.0)]);
+    let rates = charges(&[
+        (20.0, "2024-01-01T00:00:00Z", None, Some("DIRECT_DEBIT")),
+        (22.0, "2024-01-01T00:00:00Z", None, Some("NON_DIRECT_DEBIT")),
+    ]);
+
+    let direct_debit = calculate_cost(&usage, &rates, charges(&[]), &BillingOptions::default());
+    assert_close(direct_debit.energy().exc_vat, 20.0);
+
+    let options = BillingOptions { payment_method: PaymentMethod::NonDirectDebit, ..Default::default() };
+    let non_direct_debit = calculate_cost(&usage, &rates, charges(&[]), &options);
+    assert_close(non_direct_debit.energy().exc_vat, 22.0);
+}
+
+#[test]
+fn test_reports_unpriced_readings_and_converts_gas() {
+    let usage = consumption(&[
+        ("2024-01-01T10:00:00Z", "2024-01-01T10:30:00Z", 1.0),
+        ("2024-01-01T10:30:00Z", "2024-01-01T11:00:00Z", 1.0),
+    ]);
+    let rates = charges(&[(6.0, "2024-01-01T00:00:00Z", Some("2024-01-01T10:45:00Z"), None)]);
+    let options = BillingOptions { kwh_per_unit: GAS_KWH_PER_M3, ..Default::default() };
+    let bill = calculate_cost(&usage.results, &rates.results, &charges(&[]).results, &options);
+
+    assert_eq!(bill.intervals.len(), 1);
+    assert_eq!(bill.unpriced.len(), 1);
+    assert_close(bill.consumption(), GAS_KWH_PER_M3);
+    assert_close(bill.energy().exc_vat, GAS_KWH_PER_M3 * 6.0);
+}
+
+#[test]
+fn test_days_follow_uk_clock_changes() {
+    // 2024-03-31 is 23 hours long; 00:30 BST on 1 April is 23:30 UTC on 31 March.
+    let usage = consumption(&[
+        ("2024-03-31T12:00:00+01:00", "2024-03-31T12:30:00+01:00", 1.0),
+        ("2024-03-31T23:30:00Z", "2024-04-01T00:00:00Z", 1.0),
+        ("2024-10-27T01:30:00Z", "2024-10-27T02:00:00Z", 1.0),
+    ]);
+    let rates = charges(&[(20.0, "2024-01-01T00:00:00Z", None, None)]);
+    // The charge changes at midnight BST, 23:00 UTC.
+    let standing = charges(&[
+        (40.0, "2024-01-01T00:00:00Z", Some("2024-03-31T23:00:00Z"), None),
+        (60.0, "2024-03-31T23:00:00Z", Some("2024-10-27T12:00:00Z"), None),
+        (50.0, "2024-10-27T12:00:00Z", None, None),
+    ]);
+    let bill = calculate_cost(&usage, &rates, &standing, &BillingOptions::default());
+
+    let days: Vec<(NaiveDate, f64)> = bill.days.iter().map(|d| (d.date, d.standing_charge.exc_vat)).collect();
+    assert_eq!(days.len(), 3);
+    assert_eq!(days[0].0, NaiveDate::from_ymd_opt(2024, 3, 31).unwrap());
+    assert_close(days[0].1, 40.0);
+    assert_eq!(days[1].0, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap());
+    assert_close(days[1].1, 60.0);
+    // 27 October is 25 hours long; midday UTC is 13 hours in.
+    assert_eq!(days[2].0, NaiveDate::from_ymd_opt(2024, 10, 27).unwrap());
+    assert_close(days[2].1, 60.0 * 13.0 / 25.0 + 50.0 * 12.0 / 25.0);
+}
+
+#[test]
+fn test_overlapping_charges_are_not_double_counted() {
+    let usage = consumption(&[("2024-01-01T10:00:00Z", "2024-01-01T11:00:00Z", 2.0)]);
+    // An open-ended rate superseded by a newer one from 10:30.
+    let rates = charges(&[
+        (10.0, "2023-06-01T00:00:00Z", None, None),
+        (30.0, "2024-01-01T10:30:00Z", None, None),
+    ]);
+    let standing = charges(&[
+        (40.0, "2023-06-01T00:00:00Z", None, None),
+        (60.0, "2023-12-01T00:00:00Z", None, None),
+    ]);
+    let bill = calculate_cost(&usage, &rates, &standing, &BillingOptions::default());
+
+    assert!(bill.unpriced.is_empty());
+    assert_close(bill.energy().exc_vat, 1.0 * 10.0 + 1.0 * 30.0);
+    assert_close(bill.standing_charge().exc_vat, 60.0);
+}
+
+#[test]
+fn test_unpriced_days_pay_no_standing_charge() {
+    let usage = consumption(&[
+        ("2024-01-01T10:00:00Z", "2024-01-01T10:30:00Z", 1.0),
+        ("2024-01-02T10:00:00Z", "2024-01-02T10:30:00Z", 1.0),
+    ]);
+    let rates = charges(&[(20.0, "2024-01-01T00:00:00Z", Some("2024-01-02T00:00:00Z"), None)]);
+    let standing = charges(&[(40.0, "2023-01-01T00:00:00Z", None, None)]);
+    let bill = calculate_cost(&usage, &rates, &standing, &BillingOptions::default());
+
+    assert_eq!(bill.unpriced.len(), 1);
+    assert_eq!(bill.days.len(), 1);
+    assert_close(bill.standing_charge().exc_vat, 40.0);
+}
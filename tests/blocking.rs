@@ -0,0 +1,69 @@
+#![cfg(feature = "blocking")]
+
+use octopust::blocking::Client;
+use octopust::models::{ListGridSupplyPointsQuery, ProductQuery};
+use octopust::pagination::PaginationLimits;
+use octopust::OctopustError;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn gsps(group_ids: &[&str], next: Option<String>) -> ResponseTemplate {
+    let results: Vec<_> = group_ids.iter().map(|id| serde_json::json!({ "group_id": id })).collect();
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "count": 3, "next": next, "previous": null, "results": results
+    }))
+}
+
+/// The mock server runs on its own runtime; the blocking client is used
+/// from the test thread, outside of it.
+fn start_server() -> (tokio::runtime::Runtime, MockServer) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockServer::start());
+    (runtime, server)
+}
+
+#[test]
+fn test_blocking_requests_and_iterators() {
+    let (runtime, server) = start_server();
+    runtime.block_on(async {
+        Mock::given(method("GET"))
+            .and(path("/industry/grid-supply-points/"))
+            .and(query_param("page", "2"))
+            .respond_with(gsps(&["_C"], None))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/industry/grid-supply-points/"))
+            .respond_with(gsps(&["_A", "_B"], Some(format!("{}/industry/grid-supply-points/?page=2", server.uri()))))
+            .mount(&server)
+            .await;
+    });
+
+    let client = Client::builder().base_url(server.uri()).build_blocking().unwrap();
+    let page = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery::default()).unwrap();
+    assert_eq!(page.results.len(), 2);
+
+    let all: Vec<String> = client
+        .stream_industry_grid_supply_points(ListGridSupplyPointsQuery::default(), PaginationLimits::default())
+        .map(|gsp| gsp.unwrap().group_id)
+        .collect();
+    assert_eq!(all, vec!["_A", "_B", "_C"]);
+}
+
+#[test]
+fn test_blocking_errors_match_async_client() {
+    let (runtime, server) = start_server();
+    runtime.block_on(
+        Mock::given(method("GET"))
+            .and(path("/products/"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({ "detail": "Not found." })))
+            .mount(&server),
+    );
+
+    let client = Client::builder().base_url(server.uri()).build_blocking().unwrap();
+    let err = client.list_products(ProductQuery::default()).unwrap_err();
+    assert!(err.is_not_found());
+
+    let err = client.get_account("A-1234ABCD").unwrap_err();
+    assert!(matches!(err, OctopustError::MissingApiKey));
+}
@@ -0,0 +1,219 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use octopust::cache::CacheConfig;
+use octopust::models::{ListElectrictyConsumptionQuery, ListUnitRatesQuery, QueryTimestamp, RetrieveProductQuery};
+use octopust::{Client, OctopustError};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(feature = "chrono")]
+fn ts(s: &str) -> QueryTimestamp<'_> {
+    s.parse().unwrap()
+}
+
+#[cfg(not(feature = "chrono"))]
+fn ts(s: &str) -> QueryTimestamp<'_> {
+    s
+}
+
+fn product_body() -> serde_json::Value {
+    serde_json::json!({
+        "tariffs_active_at": "2024-06-01T00:00:00Z",
+        "code": "VAR-22-11-01",
+        "full_name": "Flexible Octopus",
+        "display_name": "Flexible Octopus",
+        "description": null,
+        "is_variable": true,
+        "is_green": false,
+        "is_tracker": false,
+        "is_prepay": false,
+        "is_business": false,
+        "is_restricted": false,
+        "term": null,
+        "available_from": null,
+        "available_to": null,
+        "brand": null,
+        "links": [],
+        "single_register_electricity_tariffs": null,
+        "dual_register_electricity_tariffs": null,
+        "single_register_gas_tariffs": null,
+        "sample_quotes": null,
+        "sample_consumption": null
+    })
+}
+
+fn empty_page() -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .set_body_json(serde_json::json!({ "count": 0, "next": null, "previous": null, "results": [] }))
+}
+
+fn client(server: &MockServer, config: CacheConfig) -> Client {
+    Client::builder()
+        .api_key("sk_test")
+        .base_url(server.uri())
+        .cache(config)
+        .build()
+        .unwrap()
+}
+
+fn product_query() -> RetrieveProductQuery<'static> {
+    RetrieveProductQuery { product_code: "VAR-22-11-01", ..Default::default() }
+}
+
+#[tokio::test]
+async fn test_repeated_requests_are_served_from_memory() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/products/VAR-22-11-01/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(product_body()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server, CacheConfig::default());
+    for _ in 0..3 {
+        let product = client.retrieve_product(product_query()).await.unwrap();
+        assert_eq!(product.code, "VAR-22-11-01");
+    }
+    // Clones share the cache.
+    client.clone().retrieve_product(product_query()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_consumption_is_not_cached_by_default() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/electricity-meter-points/2000000000000/meters/21L0000000/consumption/"))
+        .respond_with(empty_page())
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = client(&server, CacheConfig::default());
+    for _ in 0..2 {
+        client
+            .list_electricity_consumption(ListElectrictyConsumptionQuery {
+                mpan: "2000000000000",
+                serial_number: "21L0000000",
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_stale_entries_are_revalidated_with_etag() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/products/VAR-22-11-01/"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/products/VAR-22-11-01/"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(product_body()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = CacheConfig { products_ttl: Some(Duration::ZERO), ..Default::default() };
+    let client = client(&server, config);
+    for _ in 0..3 {
+        let product = client.retrieve_product(product_query()).await.unwrap();
+        assert_eq!(product.display_name, "Flexible Octopus");
+    }
+}
+
+#[tokio::test]
+async fn test_past_rate_ranges_never_expire() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/products/AGILE-24-10-01/electricity-tariffs/E-1R-AGILE-24-10-01-C/standard-unit-rates/"))
+        .respond_with(empty_page())
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let config = CacheConfig { tariff_charges_ttl: Some(Duration::ZERO), ..Default::default() };
+    let client = client(&server, config);
+    let query = |period_to| ListUnitRatesQuery {
+        product_code: "AGILE-24-10-01",
+        tariff_code: "E-1R-AGILE-24-10-01-C",
+        period_from: Some(ts("2024-01-01T00:00:00Z")),
+        period_to,
+        ..Default::default()
+    };
+
+    // Past range: fetched once.
+    for _ in 0..3 {
+        let past = query(Some(ts("2024-01-02T00:00:00Z")));
+        client.list_electricity_tariff_standard_unit_rates(past).await.unwrap();
+    }
+    // Open-ended range: follows the (zero) TTL.
+    for _ in 0..2 {
+        client.list_electricity_tariff_standard_unit_rates(query(None)).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_disk_store_survives_new_clients() {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let dir = std::env::temp_dir().join(format!("octopust-cache-{}-{nanos}", std::process::id()));
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/products/VAR-22-11-01/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(product_body()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    for _ in 0..2 {
+        let client = client(&server, CacheConfig::default().with_disk_store(&dir));
+        client.retrieve_product(product_query()).await.unwrap();
+    }
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_zero_capacity_is_rejected() {
+    let config = CacheConfig { capacity: 0, ..Default::default() };
+    let err = Client::builder().cache(config).build().err().unwrap();
+    assert!(matches!(err, OctopustError::Config(_)));
+}
+
+/// Without `chrono`, timestamps are sent as given; ones that do not parse
+/// must not be mistaken for a finished range.
+#[cfg(not(feature = "chrono"))]
+#[tokio::test]
+async fn test_invalid_period_to_is_not_treated_as_past() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/products/AGILE-24-10-01/electricity-tariffs/E-1R-AGILE-24-10-01-C/standard-unit-rates/"))
+        .respond_with(empty_page())
+        .expect(6)
+        .mount(&server)
+        .await;
+
+    let config = CacheConfig { tariff_charges_ttl: Some(Duration::ZERO), ..Default::default() };
+    let client = client(&server, config);
+    for period_to in ["2023-02-29T00:00:00Z", "2024-04-31T00:00:00Z", "2024-01-01T24:00:00Z"] {
+        for _ in 0..2 {
+            let query = ListUnitRatesQuery {
+                product_code: "AGILE-24-10-01",
+                tariff_code: "E-1R-AGILE-24-10-01-C",
+                period_to: Some(period_to),
+                ..Default::default()
+            };
+            client.list_electricity_tariff_standard_unit_rates(query).await.unwrap();
+        }
+    }
+}
@@ -0,0 +1,279 @@
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::Output;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::process::Command;
+use wiremock::matchers::{header, method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A scratch directory used as the config home, so the developer's own
+/// config file and environment never leak into a test.
+fn scratch_dir() -> PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let dir = std::env::temp_dir().join(format!("octopust-cli-{}-{nanos}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+async fn octopust(server: &MockServer, args: &[&str]) -> Output {
+    octopust_with(server, &scratch_dir(), &[], args).await
+}
+
+async fn octopust_with(server: &MockServer, home: &PathBuf, env: &[(&str, &str)], args: &[&str]) -> Output {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_octopust"));
+    cmd.env_remove("OCTOPUS_API_KEY")
+        .env_remove("OCTOPUST_CONFIG")
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home)
+        .env("OCTOPUS_BASE_URL", server.uri())
+        .envs(env.iter().copied())
+        .args(args);
+    cmd.output().await.unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "octopust failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn page(results: serde_json::Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "count": results.as_array().unwrap().len(),
+        "next": null,
+        "previous": null,
+        "results": results,
+    }))
+}
+
+fn rates() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "value_exc_vat": 20.0,
+            "value_inc_vat": 21.0,
+            "valid_from": "2024-01-01T00:30:00Z",
+            "valid_to": "2024-01-01T01:00:00Z",
+            "payment_method": null
+        },
+        {
+            "value_exc_vat": 10.0,
+            "value_inc_vat": 10.5,
+            "valid_from": "2024-01-01T00:00:00Z",
+            "valid_to": "2024-01-01T00:30:00Z",
+            "payment_method": null
+        }
+    ])
+}
+
+#[tokio::test]
+async fn test_rates_as_csv() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/products/AGILE-24-10-01/electricity-tariffs/E-1R-AGILE-24-10-01-C/standard-unit-rates/"))
+        .respond_with(page(rates()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = octopust(&server, &["rates", "E-1R-AGILE-24-10-01-C", "--output", "csv"]).await;
+    let csv = stdout(&output);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("value_exc_vat,value_inc_vat,valid_from,valid_to,payment_method"));
+    assert!(lines.next().unwrap().starts_with("20.0,21.0,2024-01-01T00:30:00"));
+    assert!(lines.next().unwrap().starts_with("10.0,10.5,2024-01-01T00:00:00"));
+    assert_eq!(lines.next(), None);
+}
+
+#[tokio::test]
+async fn test_gas_standing_charges_as_ndjson() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/products/VAR-22-11-01/gas-tariffs/G-1R-VAR-22-11-01-A/standing-charges/"))
+        .and(query_param("period_from", "2024-01-01T00:00:00Z"))
+        .respond_with(page(rates()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = octopust(
+        &server,
+        &["rates", "G-1R-VAR-22-11-01-A", "--kind", "standing", "--from", "2024-01-01", "-o", "ndjson"],
+    )
+    .await;
+    let lines: Vec<serde_json::Value> = stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["value_inc_vat"], 10.5);
+}
+
+#[tokio::test]
+async fn test_gas_day_rates_are_rejected() {
+    let server = MockServer::start().await;
+    let output = octopust(&server, &["rates", "G-1R-VAR-22-11-01-A", "--kind", "day"]).await;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no day or night rates"), "{stderr}");
+}
+
+#[tokio::test]
+async fn test_products_as_table() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/products/"))
+        .and(query_param("is_green", "true"))
+        .respond_with(page(serde_json::json!([{
+            "direction": "IMPORT",
+            "code": "AGILE-24-10-01",
+            "full_name": "Agile Octopus October 2024 v1",
+            "display_name": "Agile Octopus",
+            "description": "",
+            "is_variable": true,
+            "is_green": true,
+            "is_tracker": false,
+            "is_prepay": false,
+            "is_business": false,
+            "is_restricted": false,
+            "term": 12,
+            "available_from": "2024-10-01T00:00:00+01:00",
+            "available_to": null,
+            "brand": "OCTOPUS_ENERGY",
+            "links": []
+        }])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = octopust(&server, &["products", "--green", "true"]).await;
+    let table = stdout(&output);
+    let mut lines = table.lines();
+    let header = lines.next().unwrap();
+    assert!(header.starts_with("direction  code            full_name"), "{header}");
+    let row = lines.next().unwrap();
+    assert!(row.starts_with("IMPORT     AGILE-24-10-01  Agile Octopus October 2024 v1"), "{row}");
+    assert_eq!(lines.next(), None);
+}
+
+/// A first page of consumption, followed by a second page that fails.
+async fn mount_failing_second_page(server: &MockServer) {
+    let consumption_path = "/electricity-meter-points/1200000000000/meters/21L0000000/consumption/";
+    Mock::given(method("GET"))
+        .and(path(consumption_path))
+        .and(query_param_is_missing("page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 3,
+            "next": format!("{}{}?page=2", server.uri(), consumption_path),
+            "previous": null,
+            "results": [
+                { "consumption": 0.5, "interval_start": "2024-01-01T00:00:00Z", "interval_end": "2024-01-01T00:30:00Z" },
+                { "consumption": 0.25, "interval_start": "2024-01-01T00:30:00Z", "interval_end": "2024-01-01T01:00:00Z" }
+            ]
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(consumption_path))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_ndjson_and_csv_rows_are_written_as_pages_arrive() {
+    let server = MockServer::start().await;
+    mount_failing_second_page(&server).await;
+    let env = [("OCTOPUS_API_KEY", "sk_env")];
+
+    let args = ["consumption", "electricity", "1200000000000", "21L0000000", "-o", "ndjson"];
+    let output = octopust_with(&server, &scratch_dir(), &env, &args).await;
+    assert!(!output.status.success());
+    let ndjson = String::from_utf8(output.stdout).unwrap();
+    let kwh: Vec<f64> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["consumption"].as_f64().unwrap())
+        .collect();
+    assert_eq!(kwh, vec![0.5, 0.25]);
+
+    let args = ["consumption", "electricity", "1200000000000", "21L0000000", "-o", "csv"];
+    let output = octopust_with(&server, &scratch_dir(), &env, &args).await;
+    assert!(!output.status.success());
+    let csv = String::from_utf8(output.stdout).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("consumption,interval_start,interval_end"));
+    assert!(lines.next().unwrap().starts_with("0.5,2024-01-01T00:00:00"));
+    assert!(lines.next().unwrap().starts_with("0.25,2024-01-01T00:30:00"));
+    assert_eq!(lines.next(), None);
+
+    // Table output needs every row first, so it prints nothing.
+    let args = ["consumption", "electricity", "1200000000000", "21L0000000"];
+    let output = octopust_with(&server, &scratch_dir(), &env, &args).await;
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[tokio::test]
+async fn test_consumption_uses_api_key_from_config() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/electricity-meter-points/1200000000000/meters/21L0000000/consumption/"))
+        .and(header("authorization", "Basic c2tfY29uZmlnOg=="))
+        .and(query_param("group_by", "day"))
+        .respond_with(page(serde_json::json!([{
+            "consumption": 7.5,
+            "interval_start": "2024-01-01T00:00:00Z",
+            "interval_end": "2024-01-02T00:00:00Z"
+        }])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = scratch_dir();
+    std::fs::create_dir_all(home.join("octopust")).unwrap();
+    std::fs::write(home.join("octopust").join("config.toml"), "api_key = \"sk_config\"\n").unwrap();
+
+    let args = ["consumption", "electricity", "1200000000000", "21L0000000", "--group-by", "day", "-o", "json"];
+    let output = octopust_with(&server, &home, &[], &args).await;
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(json[0]["consumption"], 7.5);
+}
+
+#[tokio::test]
+async fn test_environment_overrides_config() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/electricity-meter-points/1200000000000/"))
+        .and(header("authorization", "Basic c2tfZW52Og=="))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "gsp": "_C",
+            "mpan": "1200000000000",
+            "profile_class": 1
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = scratch_dir();
+    let config = home.join("other.toml");
+    std::fs::write(&config, "api_key = \"sk_config\"\n").unwrap();
+
+    let env = [("OCTOPUS_API_KEY", "sk_env"), ("OCTOPUST_CONFIG", config.to_str().unwrap())];
+    let args = ["meter-point", "1200000000000", "-o", "json"];
+    let output = octopust_with(&server, &home, &env, &args).await;
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(json["gsp"], "_C");
+}
+
+#[tokio::test]
+async fn test_account_without_api_key() {
+    let server = MockServer::start().await;
+    let output = octopust(&server, &["account", "A-1234ABCD"]).await;
+    assert!(!output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).is_empty());
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use octopust::{models::ListGridSupplyPointsQuery, Client, OctopustError};
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_builder_uses_base_url_and_api_key() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/industry/grid-supply-points/"))
+        .and(query_param("postcode", "SW1A"))
+        // base64("sk_test:")
+        .and(header("authorization", "Basic c2tfdGVzdDo="))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 1,
+            "next": null,
+            "previous": null,
+            "results": [{ "group_id": "_C" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::builder()
+        .api_key("sk_test")
+        .base_url(format!("{}/v1/", server.uri()))
+        .timeout(Duration::from_secs(5))
+        .user_agent("octopust-tests")
+        .build()
+        .unwrap();
+    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        postcode: Some("SW1A"),
+        ..Default::default()
+    }).await.unwrap();
+    assert_eq!(result.results[0].group_id, "_C");
+}
+
+#[tokio::test]
+async fn test_builder_accepts_custom_http_client() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/industry/grid-supply-points/"))
+        .and(header("user-agent", "custom-agent"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 0,
+            "next": null,
+            "previous": null,
+            "results": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let http = reqwest::Client::builder().user_agent("custom-agent").build().unwrap();
+    let client = Client::builder()
+        .api_key("sk_test")
+        .base_url(server.uri())
+        .http_client(http)
+        .build()
+        .unwrap();
+    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        ..Default::default()
+    }).await;
+    assert!(result.is_ok(), "list_industry_grid_supply_points failed: {:?}", result);
+}
+
+#[test]
+fn test_builder_rejects_invalid_base_url() {
+    let result = Client::builder().api_key("sk_test").base_url("not a url").build();
+    assert!(matches!(result, Err(OctopustError::Config(_))));
+}
+
+#[test]
+fn test_builder_rejects_settings_alongside_custom_client() {
+    let result = Client::builder()
+        .http_client(reqwest::Client::new())
+        .timeout(Duration::from_secs(1))
+        .build();
+    assert!(matches!(result, Err(OctopustError::Config(_))));
+}
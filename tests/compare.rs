@@ -0,0 +1,139 @@
+#![cfg(feature = "chrono")]
+
+use octopust::compare::compare_tariffs;
+use octopust::models::ConsumptionResponse;
+use octopust::{Client, OctopustError, Region};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn product(code: &str, name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "tariffs_active_at": "2024-01-31T00:00:00Z",
+        "code": code,
+        "full_name": name,
+        "display_name": name,
+        "description": null,
+        "is_variable": true,
+        "is_green": false,
+        "is_tracker": false,
+        "is_prepay": false,
+        "is_business": false,
+        "is_restricted": false,
+        "term": null,
+        "available_from": "2023-01-01T00:00:00Z",
+        "available_to": null,
+        "brand": "OCTOPUS_ENERGY",
+        "links": [],
+        "single_register_electricity_tariffs": {
+            "_C": { "direct_debit_monthly": { "code": format!("E-1R-{code}-C") } }
+        },
+        "dual_register_electricity_tariffs": {},
+        "single_register_gas_tariffs": {},
+        "sample_quotes": null,
+        "sample_consumption": null
+    })
+}
+
+fn charges(results: serde_json::Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "count": results.as_array().unwrap().len(), "next": null, "previous": null, "results": results
+    }))
+}
+
+fn charge(value: f64, from: &str, to: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "value_exc_vat": value, "value_inc_vat": value * 1.05,
+        "valid_from": from, "valid_to": to, "payment_method": null
+    })
+}
+
+async fn mount_product(server: &MockServer, code: &str, name: &str, unit_rates: serde_json::Value, standing: f64) {
+    Mock::given(method("GET"))
+        .and(path(format!("/products/{code}/")))
+        .and(query_param("tariffs_active_at", "2024-01-31T23:00:00Z"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(product(code, name)))
+        .mount(server)
+        .await;
+    let tariff_path = format!("/products/{code}/electricity-tariffs/E-1R-{code}-C");
+    Mock::given(method("GET"))
+        .and(path(format!("{tariff_path}/standard-unit-rates/")))
+        .and(query_param("period_from", "2024-01-31T23:00:00Z"))
+        .and(query_param("period_to", "2024-02-01T00:30:00Z"))
+        .and(query_param("page_size", "1500"))
+        .respond_with(charges(unit_rates))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{tariff_path}/standing-charges/")))
+        .respond_with(charges(serde_json::json!([charge(standing, "2023-01-01T00:00:00Z", None)])))
+        .mount(server)
+        .await;
+}
+
+fn consumption() -> ConsumptionResponse {
+    serde_json::from_value(serde_json::json!({
+        "count": 3, "next": null, "previous": null,
+        "results": [
+            { "consumption": 1.0, "interval_start": "2024-01-31T23:00:00Z", "interval_end": "2024-01-31T23:30:00Z" },
+            { "consumption": 2.0, "interval_start": "2024-01-31T23:30:00Z", "interval_end": "2024-02-01T00:00:00Z" },
+            { "consumption": 4.0, "interval_start": "2024-02-01T00:00:00Z", "interval_end": "2024-02-01T00:30:00Z" }
+        ]
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_compare_tariffs_by_month() {
+    let server = MockServer::start().await;
+    mount_product(
+        &server,
+        "AGILE-24-10-01",
+        "Agile Octopus",
+        serde_json::json!([
+            charge(-1.0, "2024-02-01T00:00:00Z", Some("2024-02-01T00:30:00Z")),
+            charge(30.0, "2024-01-31T23:30:00Z", Some("2024-02-01T00:00:00Z")),
+            charge(10.0, "2024-01-31T23:00:00Z", Some("2024-01-31T23:30:00Z")),
+        ]),
+        50.0,
+    )
+    .await;
+    mount_product(
+        &server,
+        "VAR-22-11-01",
+        "Flexible Octopus",
+        serde_json::json!([charge(25.0, "2023-01-01T00:00:00Z", None)]),
+        40.0,
+    )
+    .await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let results = compare_tariffs(&client, &consumption(), &["AGILE-24-10-01", "VAR-22-11-01"], Region::London)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    let agile = &results[0];
+    assert_eq!(agile.display_name, "Agile Octopus");
+    assert_eq!(agile.tariff_code.as_str(), "E-1R-AGILE-24-10-01-C");
+    assert_eq!(agile.unpriced, 0);
+    let months: Vec<_> = agile.months.iter().map(|m| (m.year, m.month, m.total().exc_vat)).collect();
+    assert_eq!(months, vec![(2024, 1, 10.0 + 60.0 + 50.0), (2024, 2, -4.0 + 50.0)]);
+
+    let flexible = &results[1];
+    assert!((flexible.total().exc_vat - (7.0 * 25.0 + 2.0 * 40.0)).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_compare_tariffs_reports_missing_region() {
+    let server = MockServer::start().await;
+    mount_product(&server, "VAR-22-11-01", "Flexible Octopus", serde_json::json!([]), 40.0).await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let err = compare_tariffs(&client, &consumption(), &["VAR-22-11-01"], Region::Yorkshire)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OctopustError::InvalidInput(_)));
+
+    let err = compare_tariffs(&client, Vec::new(), &["VAR-22-11-01"], Region::London).await.unwrap_err();
+    assert!(matches!(err, OctopustError::InvalidInput(_)));
+}
@@ -0,0 +1,209 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use octopust::Client;
+use wiremock::matchers::{body_string_contains, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_login(server: &MockServer) {
+    let exp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 3600;
+    Mock::given(method("POST"))
+        .and(body_string_contains("obtainKrakenToken"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": { "obtainKrakenToken": { "token": "jwt-token", "payload": { "exp": exp } } }
+        })))
+        .mount(server)
+        .await;
+}
+
+fn client_for(server: &MockServer) -> Client {
+    Client::builder()
+        .api_key("sk_test")
+        .graphql_url(format!("{}/graphql/", server.uri()))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_planned_and_completed_dispatches() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("plannedDispatches"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "plannedDispatches": [{
+                    "start": "2024-06-01T14:00:00+01:00",
+                    "end": "2024-06-01T15:00:00+01:00",
+                    "delta": "-3.25",
+                    "meta": { "source": "smart-charge", "location": "AT_HOME" }
+                }]
+            }
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("completedDispatches"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "completedDispatches": [{
+                    "start": "2024-05-31T02:00:00+01:00",
+                    "end": "2024-05-31T04:00:00+01:00",
+                    "delta": -7.5,
+                    "meta": { "source": "bump-charge", "location": null }
+                }]
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server);
+    let planned = client.kraken().planned_dispatches("A-1234ABCD").await.unwrap();
+    assert_eq!(planned.len(), 1);
+    assert_eq!(planned[0].delta_kwh, -3.25);
+    assert_eq!(planned[0].source(), Some("smart-charge"));
+
+    let completed = client.kraken().completed_dispatches("A-1234ABCD").await.unwrap();
+    assert_eq!(completed[0].delta_kwh, -7.5);
+    assert_eq!(completed[0].source(), Some("bump-charge"));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_effective_rates_apply_off_peak_to_dispatched_slots() {
+    use chrono::{TimeZone, Utc};
+    use octopust::graphql::{dispatches::effective_rates, models::Dispatch};
+    use octopust::models::{PaymentMethod, TariffCharge};
+
+    let rates: Vec<TariffCharge> = serde_json::from_value(serde_json::json!([
+        {
+            "value_exc_vat": 6.67, "value_inc_vat": 7.0,
+            "valid_from": "2024-06-01T22:30:00Z", "valid_to": "2024-06-02T04:30:00Z",
+            "payment_method": null
+        },
+        {
+            "value_exc_vat": 28.57, "value_inc_vat": 30.0,
+            "valid_from": "2024-06-01T04:30:00Z", "valid_to": "2024-06-01T22:30:00Z",
+            "payment_method": null
+        }
+    ])).unwrap();
+    // 14:10–14:40 BST overlaps the 13:00 and 13:30 UTC slots.
+    let dispatches: Vec<Dispatch> = serde_json::from_value(serde_json::json!([{
+        "start": "2024-06-01T14:10:00+01:00",
+        "end": "2024-06-01T14:40:00+01:00",
+        "delta": "-1.1",
+        "meta": null
+    }])).unwrap();
+
+    let slots = effective_rates(
+        &rates,
+        &dispatches,
+        PaymentMethod::DirectDebit,
+        Utc.with_ymd_and_hms(2024, 6, 1, 12, 30, 0).unwrap(),
+        Utc.with_ymd_and_hms(2024, 6, 1, 14, 0, 0).unwrap(),
+    );
+    let prices: Vec<(u32, f64, bool)> = slots
+        .iter()
+        .map(|s| (s.start.format("%H%M").to_string().parse().unwrap(), s.value_inc_vat, s.dispatched))
+        .collect();
+    assert_eq!(prices, vec![
+        (1230, 30.0, false),
+        (1300, 7.0, true),
+        (1330, 7.0, true),
+    ]);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_effective_rates_use_off_peak_rate_in_force() {
+    use chrono::{TimeZone, Utc};
+    use octopust::graphql::{dispatches::effective_rates, models::Dispatch};
+    use octopust::models::{PaymentMethod, TariffCharge};
+
+    let rates: Vec<TariffCharge> = serde_json::from_value(serde_json::json!([
+        {
+            "value_exc_vat": 8.0, "value_inc_vat": 8.4,
+            "valid_from": "2024-03-01T23:30:00Z", "valid_to": "2024-03-02T05:30:00Z",
+            "payment_method": null
+        },
+        {
+            "value_exc_vat": 28.57, "value_inc_vat": 30.0,
+            "valid_from": "2024-03-01T05:30:00Z", "valid_to": "2024-03-01T23:30:00Z",
+            "payment_method": null
+        },
+        {
+            "value_exc_vat": 4.76, "value_inc_vat": 5.0,
+            "valid_from": "2023-03-01T23:30:00Z", "valid_to": "2023-03-02T05:30:00Z",
+            "payment_method": null
+        }
+    ])).unwrap();
+    // One dispatch over a peak slot, one inside the off-peak window.
+    let dispatches: Vec<Dispatch> = serde_json::from_value(serde_json::json!([
+        { "start": "2024-03-01T22:00:00Z", "end": "2024-03-01T22:30:00Z", "delta": "-2", "meta": null },
+        { "start": "2024-03-02T01:00:00Z", "end": "2024-03-02T01:30:00Z", "delta": "-2", "meta": null }
+    ])).unwrap();
+
+    let slots = effective_rates(
+        &rates,
+        &dispatches,
+        PaymentMethod::DirectDebit,
+        Utc.with_ymd_and_hms(2024, 3, 1, 22, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2024, 3, 2, 1, 30, 0).unwrap(),
+    );
+    let peak = &slots[0];
+    assert_eq!((peak.value_inc_vat, peak.dispatched), (8.4, true));
+    let off_peak = slots.last().unwrap();
+    assert_eq!(off_peak.start, Utc.with_ymd_and_hms(2024, 3, 2, 1, 0, 0).unwrap());
+    assert_eq!((off_peak.value_inc_vat, off_peak.dispatched), (8.4, false));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_effective_rates_keep_to_one_payment_method() {
+    use chrono::{TimeZone, Utc};
+    use octopust::graphql::{dispatches::effective_rates, models::Dispatch};
+    use octopust::models::{PaymentMethod, TariffCharge};
+
+    // Both payment methods, plus a Direct Debit price rise that overlaps
+    // the older open-ended rate.
+    let rates: Vec<TariffCharge> = serde_json::from_value(serde_json::json!([
+        {
+            "value_exc_vat": 30.0, "value_inc_vat": 31.5,
+            "valid_from": "2024-06-01T04:30:00Z", "valid_to": "2024-06-01T22:30:00Z",
+            "payment_method": "NON_DIRECT_DEBIT"
+        },
+        {
+            "value_exc_vat": 6.0, "value_inc_vat": 6.3,
+            "valid_from": "2024-05-31T22:30:00Z", "valid_to": "2024-06-01T04:30:00Z",
+            "payment_method": "NON_DIRECT_DEBIT"
+        },
+        {
+            "value_exc_vat": 20.0, "value_inc_vat": 21.0,
+            "valid_from": "2024-05-01T00:00:00Z", "valid_to": null,
+            "payment_method": "DIRECT_DEBIT"
+        },
+        {
+            "value_exc_vat": 28.0, "value_inc_vat": 29.4,
+            "valid_from": "2024-06-01T04:30:00Z", "valid_to": "2024-06-01T22:30:00Z",
+            "payment_method": "DIRECT_DEBIT"
+        },
+        {
+            "value_exc_vat": 7.0, "value_inc_vat": 7.35,
+            "valid_from": "2024-05-31T22:30:00Z", "valid_to": "2024-06-01T04:30:00Z",
+            "payment_method": "DIRECT_DEBIT"
+        }
+    ])).unwrap();
+    let dispatches: Vec<Dispatch> = serde_json::from_value(serde_json::json!([
+        { "start": "2024-06-01T13:00:00Z", "end": "2024-06-01T13:30:00Z", "delta": "-2", "meta": null }
+    ])).unwrap();
+    let from = Utc.with_ymd_and_hms(2024, 6, 1, 12, 30, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2024, 6, 1, 13, 30, 0).unwrap();
+
+    let prices = |payment_method| -> Vec<(f64, bool)> {
+        effective_rates(&rates, &dispatches, payment_method, from, to)
+            .iter()
+            .map(|s| (s.value_inc_vat, s.dispatched))
+            .collect()
+    };
+    assert_eq!(prices(PaymentMethod::DirectDebit), vec![(29.4, false), (7.35, true)]);
+    assert_eq!(prices(PaymentMethod::NonDirectDebit), vec![(31.5, false), (6.3, true)]);
+}
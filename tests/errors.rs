@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use octopust::{
+    models::{ListGridSupplyPointsQuery, ListUnitRatesQuery, RetrieveProductQuery},
+    retry::RetryPolicy,
+    Client, OctopustError,
+};
+use reqwest::StatusCode;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn client_for(response: ResponseTemplate) -> (MockServer, Client) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET")).respond_with(response).mount(&server).await;
+    let client = Client::builder()
+        .api_key("sk_test")
+        .base_url(server.uri())
+        .timeout(Duration::from_millis(200))
+        .retry_policy(RetryPolicy::disabled())
+        .build()
+        .unwrap();
+    (server, client)
+}
+
+async fn list_gsps(client: &Client) -> OctopustError {
+    client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        ..Default::default()
+    }).await.unwrap_err()
+}
+
+#[tokio::test]
+async fn test_auth_failures() {
+    for status in [401, 403] {
+        let (_server, client) = client_for(ResponseTemplate::new(status)).await;
+        let err = list_gsps(&client).await;
+        assert!(matches!(err, OctopustError::Auth(_)), "{status}: {err:?}");
+        assert!(err.is_auth());
+        assert!(!err.is_retryable());
+    }
+}
+
+#[tokio::test]
+async fn test_not_found() {
+    let (_server, client) = client_for(ResponseTemplate::new(404)).await;
+    let err = client.retrieve_product(RetrieveProductQuery {
+        product_code: "NO-SUCH-PRODUCT",
+        ..Default::default()
+    }).await.unwrap_err();
+    assert!(err.is_not_found());
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+#[tokio::test]
+async fn test_rate_limited_carries_retry_after() {
+    let (_server, client) =
+        client_for(ResponseTemplate::new(429).insert_header("retry-after", "7")).await;
+    let err = list_gsps(&client).await;
+    assert!(err.is_rate_limited());
+    assert!(err.is_retryable());
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+}
+
+#[tokio::test]
+async fn test_server_errors_are_retryable() {
+    let (_server, client) = client_for(ResponseTemplate::new(503)).await;
+    let err = list_gsps(&client).await;
+    assert!(matches!(err, OctopustError::Api(_)));
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_decode_error_reports_path() {
+    let (_server, client) = client_for(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "count": 1,
+        "next": null,
+        "previous": null,
+        "results": [{ "group_id": 12 }]
+    }))).await;
+    let err = list_gsps(&client).await;
+    match err {
+        OctopustError::Decode(decode) => {
+            assert_eq!(decode.status, StatusCode::OK);
+            assert_eq!(decode.path, "results[0].group_id");
+            assert!(decode.body.contains("group_id"));
+        }
+        other => panic!("expected decode error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_timeout() {
+    let (_server, client) = client_for(
+        ResponseTemplate::new(200).set_delay(Duration::from_secs(2)),
+    ).await;
+    let err = list_gsps(&client).await;
+    assert!(err.is_timeout(), "{err:?}");
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_invalid_input_is_rejected_before_sending() {
+    let (server, client) = client_for(ResponseTemplate::new(200)).await;
+    let err = client.list_electricity_tariff_standard_unit_rates(ListUnitRatesQuery {
+        product_code: "AGILE-24-10-01",
+        tariff_code: "",
+        ..Default::default()
+    }).await.unwrap_err();
+    assert!(matches!(err, OctopustError::InvalidInput(_)), "{err:?}");
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_field_errors_are_parsed() {
+    let (_server, client) = client_for(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+        "period_from": ["Invalid period_from", "Must be ISO 8601"],
+        "non_field_errors": ["period_to must be after period_from"]
+    }))).await;
+    let err = list_gsps(&client).await;
+    let api = err.api_error().expect("expected an API error");
+    assert_eq!(api.status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        api.field_error("period_from"),
+        Some(&["Invalid period_from".to_string(), "Must be ISO 8601".to_string()][..])
+    );
+    assert_eq!(api.non_field_errors, vec!["period_to must be after period_from"]);
+    assert!(api.detail.is_none());
+    assert!(api.body.contains("Invalid period_from"));
+    assert!(api.message.contains("period_from: Invalid period_from"));
+}
+
+#[tokio::test]
+async fn test_detail_is_parsed() {
+    let (_server, client) = client_for(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+        "detail": "Authentication credentials were not provided."
+    }))).await;
+    let err = list_gsps(&client).await;
+    let api = err.api_error().unwrap();
+    assert_eq!(api.detail.as_deref(), Some("Authentication credentials were not provided."));
+    assert_eq!(api.message, "Authentication credentials were not provided.");
+}
+
+#[tokio::test]
+async fn test_non_json_error_body_is_kept_raw() {
+    let (_server, client) =
+        client_for(ResponseTemplate::new(502).set_body_string("<html>Bad Gateway</html>")).await;
+    let err = list_gsps(&client).await;
+    let api = err.api_error().unwrap();
+    assert!(api.field_errors.is_empty());
+    assert_eq!(api.body, "<html>Bad Gateway</html>");
+}
@@ -0,0 +1,200 @@
+use octopust::export::CsvWriter;
+use octopust::models::{ConsumptionReading, ListElectrictyConsumptionQuery, ListUnitRatesQuery, TariffCharge};
+use octopust::pagination::PaginationLimits;
+use octopust::{Client, OctopustError};
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const CONSUMPTION_PATH: &str = "/electricity-meter-points/2000000000000/meters/21L0000000/consumption/";
+const RATES_PATH: &str = "/products/AGILE-24-10-01/electricity-tariffs/E-1R-AGILE-24-10-01-C/standard-unit-rates/";
+
+fn reading(consumption: f64, start: &str, end: &str) -> serde_json::Value {
+    serde_json::json!({ "consumption": consumption, "interval_start": start, "interval_end": end })
+}
+
+fn charges() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "value_exc_vat": 20.5,
+            "value_inc_vat": 21.525,
+            "valid_from": "2024-01-01T00:30:00Z",
+            "valid_to": null,
+            "payment_method": "DIRECT_DEBIT"
+        },
+        {
+            "value_exc_vat": 10.0,
+            "value_inc_vat": 10.5,
+            "valid_from": "2024-01-01T00:00:00Z",
+            "valid_to": "2024-01-01T00:30:00Z",
+            "payment_method": null
+        }
+    ])
+}
+
+/// Two pages of consumption; the second one is `second_page`.
+async fn mount_consumption(server: &MockServer, second_page: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(path(CONSUMPTION_PATH))
+        .and(query_param_is_missing("page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 3,
+            "next": format!("{}{}?page=2", server.uri(), CONSUMPTION_PATH),
+            "previous": null,
+            "results": [
+                reading(0.25, "2024-06-01T00:00:00+01:00", "2024-06-01T00:30:00+01:00"),
+                reading(0.5, "2024-06-01T00:30:00+01:00", "2024-06-01T01:00:00+01:00"),
+            ]
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(CONSUMPTION_PATH))
+        .and(query_param("page", "2"))
+        .respond_with(second_page)
+        .mount(server)
+        .await;
+}
+
+fn last_page() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "count": 3,
+        "next": null,
+        "previous": null,
+        "results": [reading(1.125, "2024-06-01T01:00:00+01:00", "2024-06-01T01:30:00+01:00")]
+    }))
+}
+
+fn consumption_query() -> ListElectrictyConsumptionQuery<'static> {
+    ListElectrictyConsumptionQuery {
+        mpan: "2000000000000",
+        serial_number: "21L0000000",
+        ..Default::default()
+    }
+}
+
+fn rates_query() -> ListUnitRatesQuery<'static> {
+    ListUnitRatesQuery {
+        product_code: "AGILE-24-10-01",
+        tariff_code: "E-1R-AGILE-24-10-01-C",
+        ..Default::default()
+    }
+}
+
+async fn mount_rates(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path(RATES_PATH))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 2,
+            "next": null,
+            "previous": null,
+            "results": charges()
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_csv_export_across_pages() {
+    let server = MockServer::start().await;
+    mount_consumption(&server, last_page()).await;
+    let client = Client::builder().api_key("sk_test").base_url(server.uri()).build().unwrap();
+
+    let mut csv = CsvWriter::new(Vec::new()).unwrap();
+    let stream = client.stream_electricity_consumption(consumption_query(), PaginationLimits::default());
+    assert_eq!(csv.write_stream(stream).await.unwrap(), 3);
+    assert_eq!(csv.rows(), 3);
+
+    let out = String::from_utf8(csv.finish().unwrap()).unwrap();
+    assert_eq!(
+        out,
+        "consumption,interval_start,interval_end\n\
+         0.25,2024-06-01T00:00:00+01:00,2024-06-01T00:30:00+01:00\n\
+         0.5,2024-06-01T00:30:00+01:00,2024-06-01T01:00:00+01:00\n\
+         1.125,2024-06-01T01:00:00+01:00,2024-06-01T01:30:00+01:00\n"
+    );
+}
+
+#[tokio::test]
+async fn test_csv_export_of_tariff_charges() {
+    let server = MockServer::start().await;
+    mount_rates(&server).await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let mut csv = CsvWriter::new(Vec::new()).unwrap();
+    let stream = client.stream_electricity_tariff_standard_unit_rates(rates_query(), PaginationLimits::default());
+    csv.write_stream(stream).await.unwrap();
+
+    let out = String::from_utf8(csv.finish().unwrap()).unwrap();
+    assert_eq!(
+        out,
+        "value_exc_vat,value_inc_vat,valid_from,valid_to,payment_method\n\
+         20.5,21.525,2024-01-01T00:30:00Z,,DIRECT_DEBIT\n\
+         10,10.5,2024-01-01T00:00:00Z,2024-01-01T00:30:00Z,\n"
+    );
+}
+
+#[test]
+fn test_empty_csv_export_has_header() {
+    let csv = CsvWriter::<_, TariffCharge>::new(Vec::new()).unwrap();
+    let out = String::from_utf8(csv.finish().unwrap()).unwrap();
+    assert_eq!(out, "value_exc_vat,value_inc_vat,valid_from,valid_to,payment_method\n");
+}
+
+#[tokio::test]
+async fn test_csv_export_stops_at_failed_page() {
+    let server = MockServer::start().await;
+    mount_consumption(&server, ResponseTemplate::new(404)).await;
+    let client = Client::builder().api_key("sk_test").base_url(server.uri()).build().unwrap();
+
+    let mut csv = CsvWriter::<_, ConsumptionReading>::new(Vec::new()).unwrap();
+    let stream = client.stream_electricity_consumption(consumption_query(), PaginationLimits::default());
+    let err = csv.write_stream(stream).await.unwrap_err();
+    assert!(matches!(err, OctopustError::NotFound(_)), "{err:?}");
+    assert_eq!(csv.rows(), 2);
+}
+
+#[cfg(feature = "arrow")]
+#[tokio::test]
+async fn test_parquet_export_columns() {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Float64Type, TimestampMicrosecondType};
+    use arrow_array::Array;
+    use arrow_schema::{DataType, TimeUnit};
+    use octopust::export::ParquetWriter;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let server = MockServer::start().await;
+    mount_rates(&server).await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let mut parquet = ParquetWriter::new(Vec::new()).unwrap();
+    let stream = client.stream_electricity_tariff_standard_unit_rates(rates_query(), PaginationLimits::default());
+    assert_eq!(parquet.write_stream(stream).await.unwrap(), 2);
+    let file = bytes::Bytes::from(parquet.finish().unwrap());
+
+    let batches: Vec<_> = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+    let schema = batch.schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Float64);
+    assert_eq!(
+        schema.field(2).data_type(),
+        &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+    );
+
+    let inc_vat = batch.column(1).as_primitive::<Float64Type>();
+    assert_eq!(inc_vat.values().to_vec(), vec![21.525, 10.5]);
+    let valid_from = batch.column(2).as_primitive::<TimestampMicrosecondType>();
+    assert_eq!(valid_from.value(1), 1_704_067_200_000_000);
+    let valid_to = batch.column(3).as_primitive::<TimestampMicrosecondType>();
+    assert!(valid_to.is_null(0));
+    assert_eq!(valid_to.value(1), 1_704_069_000_000_000);
+    let payment_method = batch.column(4).as_string::<i32>();
+    assert_eq!(payment_method.value(0), "DIRECT_DEBIT");
+    assert!(payment_method.is_null(1));
+}
@@ -0,0 +1,104 @@
+use octopust::{Client, OctopustError};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn account_fixture() -> serde_json::Value {
+    serde_json::json!({
+        "number": "A-1234ABCD",
+        "properties": [{
+            "id": 1000001,
+            "moved_in_at": "2021-03-01T00:00:00Z",
+            "moved_out_at": null,
+            "address_line_1": "1 Example Street",
+            "address_line_2": "",
+            "address_line_3": "",
+            "town": "LONDON",
+            "county": "",
+            "postcode": "SW1A 1AA",
+            "electricity_meter_points": [
+                {
+                    "mpan": "1000000000000",
+                    "profile_class": 1,
+                    "consumption_standard": 2900,
+                    "meters": [{
+                        "serial_number": "21L0000000",
+                        "registers": [{
+                            "identifier": "1",
+                            "rate": "STANDARD",
+                            "is_settlement_register": true
+                        }]
+                    }],
+                    "agreements": [
+                        {
+                            "tariff_code": "E-1R-VAR-22-11-01-C",
+                            "valid_from": "2021-03-01T00:00:00Z",
+                            "valid_to": "2023-12-01T00:00:00Z"
+                        },
+                        {
+                            "tariff_code": "E-1R-AGILE-24-10-01-C",
+                            "valid_from": "2023-12-01T00:00:00Z",
+                            "valid_to": null
+                        }
+                    ],
+                    "is_export": false
+                },
+                {
+                    "mpan": "1000000000001",
+                    "profile_class": 8,
+                    "consumption_standard": 0,
+                    "meters": [{ "serial_number": "21L0000000", "registers": [] }],
+                    "agreements": [],
+                    "is_export": true
+                }
+            ],
+            "gas_meter_points": [{
+                "mprn": "3000000000",
+                "consumption_standard": 12000,
+                "meters": [{ "serial_number": "G4A00000000000" }],
+                "agreements": [{
+                    "tariff_code": "G-1R-VAR-22-11-01-C",
+                    "valid_from": "2021-03-01T00:00:00Z",
+                    "valid_to": null
+                }]
+            }]
+        }]
+    })
+}
+
+#[tokio::test]
+async fn test_get_account() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/accounts/A-1234ABCD/"))
+        .and(header("authorization", "Basic c2tfdGVzdDo="))
+        .respond_with(ResponseTemplate::new(200).set_body_json(account_fixture()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = Client::builder()
+        .api_key("sk_test")
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+
+    let account = client.get_account("A-1234ABCD").await.unwrap();
+    assert_eq!(account.number, "A-1234ABCD");
+
+    let meter_points: Vec<_> = account.electricity_meter_points().collect();
+    assert_eq!(meter_points.len(), 2);
+    assert!(!meter_points[0].is_export);
+    assert!(meter_points[1].is_export);
+    assert_eq!(meter_points[0].meters[0].serial_number, "21L0000000");
+    assert_eq!(meter_points[0].agreements[1].tariff_code, "E-1R-AGILE-24-10-01-C");
+    assert!(meter_points[0].agreements[1].valid_to.is_none());
+
+    let gas: Vec<_> = account.gas_meter_points().collect();
+    assert_eq!(gas[0].mprn, "3000000000");
+    assert!(gas[0].meters[0].registers.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_account_requires_api_key() {
+    let err = Client::anonymous().get_account("A-1234ABCD").await.unwrap_err();
+    assert!(matches!(err, OctopustError::MissingApiKey));
+}
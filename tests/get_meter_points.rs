@@ -0,0 +1,39 @@
+use octopust::{Client, OctopustError};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn client_for(server: &MockServer) -> Client {
+    Client::builder()
+        .api_key("sk_test")
+        .base_url(server.uri())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_get_electricity_meter_point() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/electricity-meter-points/2000024512368/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "gsp": "_H",
+            "mpan": "2000024512368",
+            "profile_class": 1
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let info = client_for(&server).await
+        .get_electricity_meter_point("2000024512368")
+        .await
+        .unwrap();
+    assert_eq!(info.gsp, "_H");
+    assert_eq!(info.profile_class, 1);
+}
+
+#[tokio::test]
+async fn test_meter_point_lookup_requires_api_key() {
+    let err = Client::anonymous().get_electricity_meter_point("2000024512368").await.unwrap_err();
+    assert!(matches!(err, OctopustError::MissingApiKey));
+}
@@ -0,0 +1,165 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use octopust::{Client, OctopustError};
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn exp_in(seconds: u64) -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + seconds
+}
+
+async fn mount_login(server: &MockServer, exp: u64, expected_logins: u64) {
+    Mock::given(method("POST"))
+        .and(path("/graphql/"))
+        .and(body_string_contains("obtainKrakenToken"))
+        .and(body_string_contains("sk_test"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "obtainKrakenToken": {
+                    "token": "jwt-token",
+                    "payload": { "exp": exp }
+                }
+            }
+        })))
+        .expect(expected_logins)
+        .mount(server)
+        .await;
+}
+
+fn balance_response() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "data": {
+            "account": { "number": "A-1234ABCD", "balance": 4250, "overdueBalance": 0 }
+        }
+    }))
+}
+
+fn client_for(server: &MockServer) -> Client {
+    Client::builder()
+        .api_key("sk_test")
+        .graphql_url(format!("{}/graphql/", server.uri()))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_token_is_cached_between_queries() {
+    let server = MockServer::start().await;
+    mount_login(&server, exp_in(3600), 1).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("AccountBalance"))
+        .and(header("authorization", "jwt-token"))
+        .respond_with(balance_response())
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server);
+    let balance = client.kraken().account_balance("A-1234ABCD").await.unwrap();
+    assert_eq!(balance.balance, 4250);
+    // A clone shares the cached token.
+    client.clone().kraken().account_balance("A-1234ABCD").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_token_is_refreshed_before_expiry() {
+    let server = MockServer::start().await;
+    // Inside the refresh margin, so every call logs in again.
+    mount_login(&server, exp_in(60), 2).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("AccountBalance"))
+        .respond_with(balance_response())
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server);
+    client.kraken().account_balance("A-1234ABCD").await.unwrap();
+    client.kraken().account_balance("A-1234ABCD").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_expired_token_error_triggers_login_and_retry() {
+    let server = MockServer::start().await;
+    mount_login(&server, exp_in(3600), 2).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("AccountBalance"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": null,
+            "errors": [{
+                "message": "Signature of the JWT has expired.",
+                "extensions": { "errorCode": "KT-CT-1124" }
+            }]
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("AccountBalance"))
+        .respond_with(balance_response())
+        .mount(&server)
+        .await;
+
+    let balance = client_for(&server).kraken().account_balance("A-1234ABCD").await.unwrap();
+    assert_eq!(balance.number, "A-1234ABCD");
+}
+
+#[tokio::test]
+async fn test_graphql_errors_are_surfaced() {
+    let server = MockServer::start().await;
+    mount_login(&server, exp_in(3600), 1).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("AccountBalance"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": null,
+            "errors": [{
+                "message": "Unauthorized.",
+                "path": ["account"],
+                "extensions": { "errorCode": "KT-CT-4321", "errorDescription": "No access." }
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let err = client_for(&server).kraken().account_balance("A-1234ABCD").await.unwrap_err();
+    match err {
+        OctopustError::Graphql(errors) => {
+            assert_eq!(errors[0].message, "Unauthorized.");
+            assert_eq!(errors[0].code(), Some("KT-CT-4321"));
+        }
+        other => panic!("expected GraphQL error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_bills() {
+    let server = MockServer::start().await;
+    mount_login(&server, exp_in(3600), 1).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Bills"))
+        .and(body_string_contains("\"first\":2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "account": {
+                    "bills": {
+                        "edges": [
+                            { "node": { "id": "2", "billType": "STATEMENT", "fromDate": "2024-02-01", "toDate": "2024-02-29", "issuedDate": "2024-03-01" } },
+                            { "node": { "id": "1", "billType": "STATEMENT", "fromDate": "2024-01-01", "toDate": "2024-01-31", "issuedDate": "2024-02-01" } }
+                        ]
+                    }
+                }
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let bills = client_for(&server).kraken().bills("A-1234ABCD", 2).await.unwrap();
+    assert_eq!(bills.len(), 2);
+    assert_eq!(bills[0].from_date.as_deref(), Some("2024-02-01"));
+}
+
+#[tokio::test]
+async fn test_anonymous_client_cannot_use_graphql() {
+    let err = Client::anonymous().kraken().account_balance("A-1234ABCD").await.unwrap_err();
+    assert!(matches!(err, OctopustError::MissingApiKey));
+}
@@ -1,26 +1,22 @@
-use octopust::{models::ListUnitRatesQuery, Client};
+#![cfg(feature = "testing")]
+
+use octopust::models::{ListUnitRatesQuery, PaymentMethod};
+use octopust::testing::{fixtures, MockOctopus};
 
 #[tokio::test]
 async fn test_list_day_unit_prices() {
-    let api_key = std::env::var("OCTOPUS_API_KEY").unwrap_or_default();
-    if api_key.is_empty() {
-        eprintln!("OCTOPUS_API_KEY not set. Skipping test.");
-        return;
-    }
-    let client = Client::new(api_key);
-    let result = client.list_electricity_tariff_day_unit_rates(ListUnitRatesQuery {
-        product_code: "VAR-22-11-01",
-        tariff_code: "E-2R-VAR-22-11-01-J",
-        ..Default::default()
-    }).await;
-    println!("{:?}", result);
-    match result{
-        Ok(day_unit_rate_response) => {
-            println!("Day Unit Rate Response {:?}", day_unit_rate_response);
-        }
-        Err(e) => {
-            eprintln!("Error from day_unit_rate_response: {:?}", e);
-            panic!("test_list_day_unit_prices failed")
-        }
-    }
-}
\ No newline at end of file
+    let mock = MockOctopus::start().await;
+    let rates = mock
+        .client()
+        .list_electricity_tariff_day_unit_rates(ListUnitRatesQuery {
+            product_code: fixtures::FLEXIBLE_PRODUCT,
+            tariff_code: "E-2R-VAR-22-11-01-J",
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(rates.count, 2);
+    assert!(rates.results.iter().all(|r| r.valid_to.is_none()));
+    assert!(rates.results.iter().any(|r| r.payment_method == Some(PaymentMethod::DirectDebit)));
+    assert_eq!(mock.requests()[0].path, "/products/VAR-22-11-01/electricity-tariffs/E-2R-VAR-22-11-01-J/day-unit-rates/");
+}
@@ -1,26 +1,37 @@
-use octopust::{models::ListUnitRatesQuery, Client};
+#![cfg(feature = "testing")]
+
+use octopust::models::ListUnitRatesQuery;
+use octopust::testing::{fixtures, MockOctopus};
 
 #[tokio::test]
 async fn test_list_electricity_standard_unit_prices() {
-    let api_key = std::env::var("OCTOPUS_API_KEY").unwrap_or_default();
-    if api_key.is_empty() {
-        eprintln!("OCTOPUS_API_KEY not set. Skipping test.");
-        return;
-    }
-    let client = Client::new(api_key);
-    let result = client.list_electricity_tariff_standard_unit_rates(ListUnitRatesQuery {
-        product_code: "VAR-22-11-01",
-        tariff_code: "E-1R-VAR-22-11-01-J",
-        ..Default::default()
-    }).await;
-    println!("{:?}", result);
-    match result{
-        Ok(standard_unit_rate_response) => {
-            println!("Standard Unit Rate Response {:?}", standard_unit_rate_response);
-        }
-        Err(e) => {
-            eprintln!("Error from standard_unit_rate_response: {:?}", e);
-            panic!("test_list_electricity_standard_unit_prices failed")
-        }
-    }
-}
\ No newline at end of file
+    let mock = MockOctopus::start().await;
+    let rates = mock
+        .client()
+        .list_electricity_tariff_standard_unit_rates(ListUnitRatesQuery {
+            product_code: fixtures::FLEXIBLE_PRODUCT,
+            tariff_code: "E-1R-VAR-22-11-01-J",
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    // Direct Debit and non-Direct Debit rates, before and after the price change.
+    assert_eq!(rates.count, 4);
+    assert!(rates.results[0].valid_to.is_none());
+    assert!(rates.results[3].valid_to.is_some());
+}
+
+#[tokio::test]
+async fn test_list_electricity_standard_unit_prices_for_unknown_tariff() {
+    let mock = MockOctopus::start().await;
+    let err = mock
+        .client()
+        .list_electricity_tariff_standard_unit_rates(ListUnitRatesQuery {
+            product_code: fixtures::FLEXIBLE_PRODUCT,
+            tariff_code: "E-1R-VAR-17-01-11-J",
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert!(err.is_not_found(), "{err:?}");
+}
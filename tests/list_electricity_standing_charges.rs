@@ -1,26 +1,24 @@
-use octopust::{models::ListUnitRatesQuery, Client};
+#![cfg(feature = "testing")]
+
+use octopust::models::ListUnitRatesQuery;
+use octopust::testing::{fixtures, MockOctopus};
 
 #[tokio::test]
 async fn test_electricity_standing_charges() {
-    let api_key = std::env::var("OCTOPUS_API_KEY").unwrap_or_default();
-    if api_key.is_empty() {
-        eprintln!("OCTOPUS_API_KEY not set. Skipping test.");
-        return;
-    }
-    let client = Client::new(api_key);
-    let result = client.list_electricity_tariff_standing_charges(ListUnitRatesQuery {
-        product_code: "VAR-22-11-01",
-        tariff_code: "E-2R-VAR-22-11-01-J",
-        ..Default::default()
-    }).await;
-    println!("{:?}", result);
-    match result{
-        Ok(electricity_standing_charges_response) => {
-            println!("Electricity Standing Charges Response {:?}", electricity_standing_charges_response);
-        }
-        Err(e) => {
-            eprintln!("Error from electricity_standing_charges_response: {:?}", e);
-            panic!("test_electricity_standing_charges failed")
-        }
-    }
-}
\ No newline at end of file
+    let mock = MockOctopus::start().await;
+    let charges = mock
+        .client()
+        .list_electricity_tariff_standing_charges(ListUnitRatesQuery {
+            product_code: fixtures::FLEXIBLE_PRODUCT,
+            tariff_code: "E-2R-VAR-22-11-01-J",
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(charges.count, 2);
+    assert!(charges.results.iter().all(|c| c.value_inc_vat > c.value_exc_vat));
+    assert_eq!(
+        mock.requests()[0].path,
+        "/products/VAR-22-11-01/electricity-tariffs/E-2R-VAR-22-11-01-J/standing-charges/"
+    );
+}
@@ -1,26 +1,21 @@
-use octopust::{models::ListUnitRatesQuery, Client};
+#![cfg(feature = "testing")]
+
+use octopust::models::ListUnitRatesQuery;
+use octopust::testing::{fixtures, MockOctopus};
 
 #[tokio::test]
 async fn test_list_gas_standard_unit_prices() {
-    let api_key = std::env::var("OCTOPUS_API_KEY").unwrap_or_default();
-    if api_key.is_empty() {
-        eprintln!("OCTOPUS_API_KEY not set. Skipping test.");
-        return;
-    }
-    let client = Client::new(api_key);
-    let result = client.list_gas_tariff_standard_unit_rates(ListUnitRatesQuery {
-        product_code: "VAR-17-01-11",
-        tariff_code: "G-1R-VAR-17-01-11-A",
-        ..Default::default()
-    }).await;
-    println!("{:?}", result);
-    match result{
-        Ok(standard_unit_rate_response) => {
-            println!("Gas Standard Unit Rate Response {:?}", standard_unit_rate_response);
-        }
-        Err(e) => {
-            eprintln!("Error from standard_unit_rate_response: {:?}", e);
-            panic!("test_list_gas_standard_unit_prices failed")
-        }
-    }
-}
\ No newline at end of file
+    let mock = MockOctopus::start().await;
+    let rates = mock
+        .client()
+        .list_gas_tariff_standard_unit_rates(ListUnitRatesQuery {
+            product_code: fixtures::FLEXIBLE_PRODUCT,
+            tariff_code: "G-1R-VAR-22-11-01-A",
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(rates.count, 4);
+    assert_eq!(rates.results[0].value_exc_vat, 6.5);
+    assert_eq!(mock.requests()[0].path, "/products/VAR-22-11-01/gas-tariffs/G-1R-VAR-22-11-01-A/standard-unit-rates/");
+}
@@ -1,26 +1,25 @@
-use octopust::{models::ListUnitRatesQuery, Client};
+#![cfg(feature = "testing")]
+
+use octopust::models::{ListUnitRatesQuery, PaymentMethod};
+use octopust::testing::{fixtures, MockOctopus};
 
 #[tokio::test]
 async fn test_list_gas_standing_charges() {
-    let api_key = std::env::var("OCTOPUS_API_KEY").unwrap_or_default();
-    if api_key.is_empty() {
-        eprintln!("OCTOPUS_API_KEY not set. Skipping test.");
-        return;
-    }
-    let client = Client::new(api_key);
-    let result = client.list_gas_tariff_standing_charges(ListUnitRatesQuery {
-        product_code: "VAR-17-01-11",
-        tariff_code: "G-1R-VAR-17-01-11-A",
-        ..Default::default()
-    }).await;
-    println!("{:?}", result);
-    match result{
-        Ok(gas_standing_charges_response) => {
-            println!("Gas Standing Charges Response {:?}", gas_standing_charges_response);
-        }
-        Err(e) => {
-            eprintln!("Error from gas_standing_charges_response: {:?}", e);
-            panic!("test_list_gas_standing_charges failed")
-        }
-    }
-}
\ No newline at end of file
+    let mock = MockOctopus::start().await;
+    let charges = mock
+        .client()
+        .list_gas_tariff_standing_charges(ListUnitRatesQuery {
+            product_code: fixtures::FLEXIBLE_PRODUCT,
+            tariff_code: "G-1R-VAR-22-11-01-A",
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(charges.count, 2);
+    let direct_debit = charges
+        .results
+        .iter()
+        .find(|c| c.payment_method == Some(PaymentMethod::DirectDebit))
+        .unwrap();
+    assert_eq!(direct_debit.value_exc_vat, 29.11);
+}
@@ -1,23 +1,22 @@
-use octopust::{models::ListGridSupplyPointsQuery, Client};
+#![cfg(feature = "testing")]
+
+use octopust::models::ListGridSupplyPointsQuery;
+use octopust::testing::MockOctopus;
+use octopust::Region;
 
 #[tokio::test]
- async fn test_list_grid_supply_points() {
-    let api_key = std::env::var("OCTOPUS_API_KEY").unwrap_or_default();
-    if api_key.is_empty() {
-        eprintln!("OCTOPUS_API_KEY not set. Skipping test.");
-        return;
-    }
-    let client = Client::new(api_key);
-    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
-        ..Default::default()
-    }).await;
-    match result {
-        Ok(products) => {
-            println!("Grid supply points: {:?}", products);
-        }
-        Err(e) => {
-            eprintln!("Error from list_industry_grid_supply_points: {:?}", e);
-            panic!("list_industry_grid_supply_points failed");
-        }
-    }
-}
\ No newline at end of file
+async fn test_list_grid_supply_points() {
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+
+    let all = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery::default()).await.unwrap();
+    assert_eq!(all.count as usize, Region::ALL.len());
+    assert!(all.next.is_none());
+
+    let london = client
+        .list_industry_grid_supply_points(ListGridSupplyPointsQuery { postcode: Some("sw1a1aa"), ..Default::default() })
+        .await
+        .unwrap();
+    let ids: Vec<_> = london.results.iter().map(|p| p.group_id.as_str()).collect();
+    assert_eq!(ids, ["_C"]);
+}
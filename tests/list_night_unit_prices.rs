@@ -1,26 +1,23 @@
-use octopust::{models::ListUnitRatesQuery, Client};
+#![cfg(feature = "testing")]
+
+use octopust::models::ListUnitRatesQuery;
+use octopust::testing::{fixtures, MockOctopus};
 
 #[tokio::test]
 async fn test_list_electricity_night_unit_prices() {
-    let api_key = std::env::var("OCTOPUS_API_KEY").unwrap_or_default();
-    if api_key.is_empty() {
-        eprintln!("OCTOPUS_API_KEY not set. Skipping test.");
-        return;
-    }
-    let client = Client::new(api_key);
-    let result = client.list_electricity_tariff_night_unit_rates(ListUnitRatesQuery {
-        product_code: "VAR-22-11-01",
-        tariff_code: "E-2R-VAR-22-11-01-J",
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+    let query = |tariff_code| ListUnitRatesQuery {
+        product_code: fixtures::FLEXIBLE_PRODUCT,
+        tariff_code,
         ..Default::default()
-    }).await;
-    println!("{:?}", result);
-    match result{
-        Ok(night_unit_rate_response) => {
-            println!("Night Unit Rate Response {:?}", night_unit_rate_response);
-        }
-        Err(e) => {
-            eprintln!("Error from night_unit_rate_response: {:?}", e);
-            panic!("test_list_electricity_night_unit_prices failed")
-        }
-    }
-}
\ No newline at end of file
+    };
+    let night = client.list_electricity_tariff_night_unit_rates(query("E-2R-VAR-22-11-01-J")).await.unwrap();
+    let day = client.list_electricity_tariff_day_unit_rates(query("E-2R-VAR-22-11-01-J")).await.unwrap();
+    assert_eq!(night.count, 2);
+    assert!(night.results[0].value_inc_vat < day.results[0].value_inc_vat);
+    assert_eq!(
+        mock.requests()[0].path,
+        "/products/VAR-22-11-01/electricity-tariffs/E-2R-VAR-22-11-01-J/night-unit-rates/"
+    );
+}
@@ -1,17 +1,18 @@
-use octopust::{models::ProductQuery, Client};
+#![cfg(feature = "testing")]
+
+use octopust::models::ProductQuery;
+use octopust::testing::{fixtures, MockOctopus};
 
 #[tokio::test]
 async fn test_list_products() {
-    let api_key = std::env::var("OCTOPUS_API_KEY").unwrap_or_default();
-    if api_key.is_empty() {
-        eprintln!("OCTOPUS_API_KEY not set. Skipping test.");
-        return;
-    }
-    let client = Client::new(api_key);
-    let result = client.list_products(ProductQuery {
-        ..Default::default()
-    }).await;
-    assert!(result.is_ok(), "get_products failed: {:?}", result);
-    let products = result.unwrap();
-    println!("Products: {:?}", products);
-}
\ No newline at end of file
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+
+    let products = client.list_products(ProductQuery::default()).await.unwrap();
+    let codes: Vec<_> = products.iter().map(|p| p.code.as_str()).collect();
+    assert_eq!(codes, [fixtures::AGILE_PRODUCT, fixtures::FLEXIBLE_PRODUCT, fixtures::FIXED_PRODUCT]);
+
+    let green = client.list_products(ProductQuery { is_green: Some(&true), ..Default::default() }).await.unwrap();
+    assert_eq!(green.len(), 1);
+    assert_eq!(green[0].code, fixtures::AGILE_PRODUCT);
+}
@@ -0,0 +1,76 @@
+use octopust::{
+    models::{Direction, GroupBy, ListGasConsumptionQuery, Order, PaymentMethod, TariffCharge},
+    Client,
+};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_group_by_and_order_use_wire_values() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/gas-meter-points/3000000000/meters/G4A00000000000/consumption/"))
+        .and(query_param("group_by", "quarter"))
+        .and(query_param("order_by", "-period"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 0,
+            "next": null,
+            "previous": null,
+            "results": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = Client::builder()
+        .api_key("sk_test")
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+
+    let result = client.list_gas_consumption(ListGasConsumptionQuery {
+        mprn: "3000000000",
+        serial_number: "G4A00000000000",
+        group_by: Some(GroupBy::Quarter),
+        order_by: Some(Order::PeriodDesc),
+        ..Default::default()
+    }).await;
+    assert!(result.is_ok(), "list_gas_consumption failed: {:?}", result);
+}
+
+#[test]
+fn test_enum_wire_values() {
+    let group_bys = [
+        (GroupBy::Hour, "hour"),
+        (GroupBy::Day, "day"),
+        (GroupBy::Week, "week"),
+        (GroupBy::Month, "month"),
+        (GroupBy::Quarter, "quarter"),
+    ];
+    for (group_by, wire) in group_bys {
+        assert_eq!(group_by.to_string(), wire);
+        assert_eq!(serde_json::to_value(group_by).unwrap(), wire);
+    }
+    assert_eq!(Order::PeriodAsc.to_string(), "period");
+    assert_eq!(serde_json::to_value(Order::PeriodDesc).unwrap(), "-period");
+    assert_eq!(Direction::Import.to_string(), "IMPORT");
+    assert_eq!(serde_json::to_value(Direction::Export).unwrap(), "EXPORT");
+    assert_eq!(
+        serde_json::from_value::<Direction>(serde_json::json!("IMPORT")).unwrap(),
+        Direction::Import
+    );
+}
+
+#[test]
+fn test_payment_method_deserializes() {
+    let charge: TariffCharge = serde_json::from_value(serde_json::json!({
+        "value_exc_vat": 50.0,
+        "value_inc_vat": 52.5,
+        "valid_from": "2024-01-01T00:00:00Z",
+        "valid_to": null,
+        "payment_method": "NON_DIRECT_DEBIT"
+    })).unwrap();
+    assert_eq!(charge.payment_method, Some(PaymentMethod::NonDirectDebit));
+    for method in [PaymentMethod::DirectDebit, PaymentMethod::NonDirectDebit] {
+        assert_eq!(serde_json::to_value(method).unwrap(), method.to_string());
+    }
+}
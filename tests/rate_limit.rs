@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+use octopust::{models::ListGridSupplyPointsQuery, rate_limit::RateLimit, Client, OctopustError};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn empty_page() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "count": 0,
+        "next": null,
+        "previous": null,
+        "results": []
+    }))
+}
+
+async fn fan_out(client: &Client, n: usize) {
+    let tasks: Vec<_> = (0..n)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+                    ..Default::default()
+                }).await
+            })
+        })
+        .collect();
+    for task in tasks {
+        assert!(task.await.unwrap().is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limit_is_shared_across_clones() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET")).respond_with(empty_page()).mount(&server).await;
+    let client = Client::builder()
+        .base_url(server.uri())
+        .rate_limit(RateLimit::per_second(4).with_burst(1))
+        .build()
+        .unwrap();
+
+    let started = Instant::now();
+    fan_out(&client, 3).await;
+    // One request from the burst, then one every 250ms.
+    assert!(started.elapsed() >= Duration::from_millis(450));
+}
+
+#[tokio::test]
+async fn test_max_in_flight_caps_concurrency() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(empty_page().set_delay(Duration::from_millis(150)))
+        .mount(&server)
+        .await;
+    let client = Client::builder()
+        .base_url(server.uri())
+        .max_in_flight(1)
+        .build()
+        .unwrap();
+
+    let started = Instant::now();
+    fan_out(&client, 3).await;
+    assert!(started.elapsed() >= Duration::from_millis(450));
+}
+
+#[test]
+fn test_rejects_empty_limits() {
+    let result = Client::builder().rate_limit(RateLimit::per_second(0)).build();
+    assert!(matches!(result, Err(OctopustError::Config(_))));
+    let result = Client::builder().max_in_flight(0).build();
+    assert!(matches!(result, Err(OctopustError::Config(_))));
+}
@@ -0,0 +1,72 @@
+use octopust::{Client, OctopustError, Region};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn gsps(group_ids: &[&str]) -> ResponseTemplate {
+    let results: Vec<_> = group_ids.iter().map(|id| serde_json::json!({ "group_id": id })).collect();
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "count": results.len(),
+        "next": null,
+        "previous": null,
+        "results": results
+    }))
+}
+
+async fn client_with_postcode(postcode: &str, group_ids: &[&str]) -> (MockServer, Client) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/industry/grid-supply-points/"))
+        .and(query_param("postcode", postcode))
+        .respond_with(gsps(group_ids))
+        .mount(&server)
+        .await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+    (server, client)
+}
+
+#[test]
+fn test_region_codes_and_names() {
+    assert_eq!(Region::ALL.len(), 14);
+    assert_eq!("_C".parse::<Region>().unwrap(), Region::London);
+    assert_eq!("p".parse::<Region>().unwrap(), Region::NorthernScotland);
+    assert_eq!(Region::SouthEasternEngland.gsp_group_id(), "_J");
+    assert_eq!(Region::MerseysideAndNorthernWales.to_string(), "Merseyside and Northern Wales");
+    for bad in ["", "_", "I", "_O", "AB", "_Z"] {
+        assert!(bad.parse::<Region>().is_err(), "{bad}");
+    }
+    for region in Region::ALL {
+        assert_eq!(region.gsp_group_id().parse::<Region>().unwrap(), region);
+    }
+}
+
+#[test]
+fn test_region_serde_uses_group_id() {
+    assert_eq!(serde_json::to_string(&Region::Yorkshire).unwrap(), "\"_M\"");
+    assert_eq!(serde_json::from_str::<Region>("\"_M\"").unwrap(), Region::Yorkshire);
+}
+
+#[tokio::test]
+async fn test_region_for_postcode() {
+    let (_server, client) = client_with_postcode("SW1A 1AA", &["_C"]).await;
+    assert_eq!(client.region_for_postcode("SW1A 1AA").await.unwrap(), Region::London);
+}
+
+#[tokio::test]
+async fn test_region_for_postcode_spanning_regions() {
+    let (_server, client) = client_with_postcode("SY10", &["_D", "_E", "_D"]).await;
+    match client.region_for_postcode("SY10").await.unwrap_err() {
+        OctopustError::AmbiguousPostcode { postcode, regions } => {
+            assert_eq!(postcode, "SY10");
+            assert_eq!(regions, vec![Region::MerseysideAndNorthernWales, Region::WestMidlands]);
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[tokio::test]
+async fn test_region_for_unknown_postcode() {
+    let (_server, client) = client_with_postcode("ZZ99", &[]).await;
+    let err = client.region_for_postcode("ZZ99").await.unwrap_err();
+    assert!(matches!(err, OctopustError::InvalidInput(_)));
+    assert!(matches!(client.region_for_postcode(" ").await, Err(OctopustError::InvalidInput(_))));
+}
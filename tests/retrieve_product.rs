@@ -1,25 +1,32 @@
-use octopust::{models::RetrieveProductQuery, Client};
+#![cfg(feature = "testing")]
+
+use octopust::models::RetrieveProductQuery;
+use octopust::testing::{fixtures, MockOctopus};
+use octopust::Region;
 
 #[tokio::test]
 async fn test_retrieve_product() {
-    let api_key = std::env::var("OCTOPUS_API_KEY").unwrap_or_default();
-    if api_key.is_empty() {
-        eprintln!("OCTOPUS_API_KEY not set. Skipping test.");
-        return;
-    }
-    let client = Client::new(api_key);
-    let result = client.retrieve_product(RetrieveProductQuery { 
-        product_code: "AGILE-24-10-01",
-        ..Default::default()
-     }).await;
-    println!("{:?}", result);
-    match result {
-        Ok(products) => {
-            println!("Products: {:?}", products);
-        }
-        Err(e) => {
-            eprintln!("Error from retrieve_products: {:?}", e);
-            panic!("get_products failed");
-        }
-    }
-}
\ No newline at end of file
+    let mock = MockOctopus::start().await;
+    let product = mock
+        .client()
+        .retrieve_product(RetrieveProductQuery { product_code: fixtures::AGILE_PRODUCT, ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(product.code, fixtures::AGILE_PRODUCT);
+    let tariffs = product.single_register_electricity_tariffs.unwrap();
+    assert_eq!(tariffs.len(), Region::ALL.len());
+    let london = tariffs["_C"].direct_debit_monthly.as_ref().unwrap();
+    assert_eq!(london.code.as_deref(), Some("E-1R-AGILE-24-10-01-C"));
+    assert!(product.dual_register_electricity_tariffs.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_retrieve_unknown_product() {
+    let mock = MockOctopus::start().await;
+    let err = mock
+        .client()
+        .retrieve_product(RetrieveProductQuery { product_code: "NO-SUCH-PRODUCT", ..Default::default() })
+        .await
+        .unwrap_err();
+    assert!(err.is_not_found(), "{err:?}");
+}
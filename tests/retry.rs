@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+use octopust::{models::ListGridSupplyPointsQuery, retry::RetryPolicy, Client};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn empty_page() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "count": 0,
+        "next": null,
+        "previous": null,
+        "results": []
+    }))
+}
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(10),
+        jitter: false,
+        ..Default::default()
+    }
+}
+
+async fn mount_failures(server: &MockServer, status: u16, times: u64) {
+    Mock::given(method("GET"))
+        .and(path("/industry/grid-supply-points/"))
+        .respond_with(ResponseTemplate::new(status))
+        .up_to_n_times(times)
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/industry/grid-supply-points/"))
+        .respond_with(empty_page())
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_retries_transient_gateway_errors() {
+    let server = MockServer::start().await;
+    mount_failures(&server, 502, 2).await;
+    let client = Client::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build()
+        .unwrap();
+
+    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        ..Default::default()
+    }).await;
+    assert!(result.is_ok(), "retry did not recover: {:?}", result);
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_attempts() {
+    let server = MockServer::start().await;
+    mount_failures(&server, 503, 5).await;
+    let client = Client::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build()
+        .unwrap();
+
+    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        ..Default::default()
+    }).await;
+    assert!(result.is_err());
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_does_not_retry_client_errors_or_when_disabled() {
+    let server = MockServer::start().await;
+    mount_failures(&server, 400, 1).await;
+    let client = Client::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build()
+        .unwrap();
+    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        ..Default::default()
+    }).await;
+    assert!(result.is_err());
+
+    let server = MockServer::start().await;
+    mount_failures(&server, 503, 1).await;
+    let client = Client::builder()
+        .base_url(server.uri())
+        .retry_policy(RetryPolicy::disabled())
+        .build()
+        .unwrap();
+    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        ..Default::default()
+    }).await;
+    assert!(result.is_err());
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_honours_retry_after() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "1"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET")).respond_with(empty_page()).mount(&server).await;
+    let client = Client::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build()
+        .unwrap();
+
+    let started = Instant::now();
+    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        ..Default::default()
+    }).await;
+    assert!(result.is_ok(), "retry did not recover: {:?}", result);
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_retry_after_beyond_max_backoff_is_not_waited() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "3600"))
+        .mount(&server)
+        .await;
+    let client = Client::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build()
+        .unwrap();
+
+    let result = client.list_industry_grid_supply_points(ListGridSupplyPointsQuery {
+        ..Default::default()
+    }).await;
+    assert!(result.is_err());
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
@@ -0,0 +1,215 @@
+#![cfg(feature = "store")]
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{TimeZone, Utc};
+use octopust::retry::RetryPolicy;
+use octopust::store::{ChargeType, MeterKey, Store};
+use octopust::{Client, TariffCode};
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const CONSUMPTION_PATH: &str = "/electricity-meter-points/2000000000000/meters/21L0000000/consumption/";
+const RATES_PATH: &str = "/products/VAR-22-11-01/electricity-tariffs/E-1R-VAR-22-11-01-C/standard-unit-rates/";
+
+fn page(results: serde_json::Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "count": results.as_array().unwrap().len(), "next": null, "previous": null, "results": results
+    }))
+}
+
+fn reading(start: &str, end: &str, kwh: f64) -> serde_json::Value {
+    serde_json::json!({ "consumption": kwh, "interval_start": start, "interval_end": end })
+}
+
+fn client(server: &MockServer) -> Client {
+    Client::builder().api_key("sk_test").base_url(server.uri()).build().unwrap()
+}
+
+#[tokio::test]
+async fn test_sync_consumption_fetches_only_new_intervals() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(CONSUMPTION_PATH))
+        .and(query_param("period_from", "2024-01-01T00:00:00Z"))
+        .and(query_param("order_by", "period"))
+        .and(query_param("page_size", "25000"))
+        .respond_with(page(serde_json::json!([
+            reading("2024-01-01T00:00:00Z", "2024-01-01T00:30:00Z", 0.5),
+            reading("2024-01-01T00:30:00Z", "2024-01-01T01:00:00Z", 0.25),
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(CONSUMPTION_PATH))
+        .and(query_param("period_from", "2024-01-01T01:00:00Z"))
+        .respond_with(page(serde_json::json!([reading(
+            "2024-01-01T01:00:00Z",
+            "2024-01-01T01:30:00Z",
+            1.0
+        )])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let db = std::env::temp_dir().join(format!("octopust-store-{}-{nanos}.sqlite", std::process::id()));
+    let client = client(&server);
+    let meter = MeterKey::electricity("2000000000000", "21L0000000");
+    let since = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    let first = Store::open(&db).unwrap().sync_consumption(&client, &meter, since).await.unwrap();
+    assert_eq!(first.fetched, 2);
+
+    // A reopened database resumes from its checkpoint.
+    let store = Store::open(&db).unwrap();
+    let second = store.sync_consumption(&client, &meter, since).await.unwrap();
+    assert_eq!(second.fetched, 1);
+    assert_eq!(second.synced_to.unwrap().to_rfc3339(), "2024-01-01T01:30:00+00:00");
+
+    let all = store.consumption(&meter, None, None).unwrap();
+    let kwh: Vec<f64> = all.iter().map(|r| r.consumption).collect();
+    assert_eq!(kwh, vec![0.5, 0.25, 1.0]);
+
+    let later = store
+        .consumption(&meter, Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 30, 0).unwrap()), None)
+        .unwrap();
+    assert_eq!(later.len(), 2);
+
+    // Nothing for other meters.
+    let gas = MeterKey::gas("3000000000", "21L0000000");
+    assert!(store.consumption(&gas, None, None).unwrap().is_empty());
+    assert!(store.checkpoint(&gas).unwrap().is_none());
+
+    drop(store);
+    std::fs::remove_file(&db).unwrap();
+}
+
+#[tokio::test]
+async fn test_sync_consumption_without_new_data_keeps_checkpoint() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(CONSUMPTION_PATH))
+        .respond_with(page(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let store = Store::open_in_memory().unwrap();
+    let meter = MeterKey::electricity("2000000000000", "21L0000000");
+    let since = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let report = store.sync_consumption(&client(&server), &meter, since).await.unwrap();
+    assert_eq!(report.fetched, 0);
+    assert!(report.synced_to.is_none());
+}
+
+#[tokio::test]
+async fn test_sync_consumption_keeps_pages_before_a_failure() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(CONSUMPTION_PATH))
+        .and(query_param("period_from", "2024-01-01T00:00:00Z"))
+        .and(query_param_is_missing("page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 3,
+            "next": format!("{}{}?period_from=2024-01-01T00:00:00Z&page=2", server.uri(), CONSUMPTION_PATH),
+            "previous": null,
+            "results": [
+                reading("2024-01-01T00:00:00Z", "2024-01-01T00:30:00Z", 0.5),
+                reading("2024-01-01T00:30:00Z", "2024-01-01T01:00:00Z", 0.25),
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(CONSUMPTION_PATH))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(CONSUMPTION_PATH))
+        .and(query_param("period_from", "2024-01-01T01:00:00Z"))
+        .respond_with(page(serde_json::json!([reading(
+            "2024-01-01T01:00:00Z",
+            "2024-01-01T01:30:00Z",
+            1.0
+        )])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::builder()
+        .api_key("sk_test")
+        .base_url(server.uri())
+        .retry_policy(RetryPolicy::disabled())
+        .build()
+        .unwrap();
+    let store = Store::open_in_memory().unwrap();
+    let meter = MeterKey::electricity("2000000000000", "21L0000000");
+    let since = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    assert!(store.sync_consumption(&client, &meter, since).await.is_err());
+    assert_eq!(store.consumption(&meter, None, None).unwrap().len(), 2);
+    assert_eq!(store.checkpoint(&meter).unwrap().unwrap().to_rfc3339(), "2024-01-01T01:00:00+00:00");
+
+    // The retry picks up after the stored page.
+    let report = store.sync_consumption(&client, &meter, since).await.unwrap();
+    assert_eq!(report.fetched, 1);
+    assert_eq!(store.consumption(&meter, None, None).unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_sync_tariff_charges_refreshes_open_ended_charge() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(RATES_PATH))
+        .and(query_param("period_from", "2024-01-01T00:00:00Z"))
+        .respond_with(page(serde_json::json!([{
+            "value_exc_vat": 25.0, "value_inc_vat": 26.25, "valid_from": "2024-01-01T00:00:00Z",
+            "valid_to": null, "payment_method": "DIRECT_DEBIT"
+        }])))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(RATES_PATH))
+        .and(query_param("period_from", "2024-01-01T00:00:00Z"))
+        .respond_with(page(serde_json::json!([
+            { "value_exc_vat": 24.0, "value_inc_vat": 25.2, "valid_from": "2024-04-01T00:00:00Z",
+              "valid_to": null, "payment_method": "DIRECT_DEBIT" },
+            { "value_exc_vat": 25.0, "value_inc_vat": 26.25, "valid_from": "2024-01-01T00:00:00Z",
+              "valid_to": "2024-04-01T00:00:00Z", "payment_method": "DIRECT_DEBIT" }
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let store = Store::open_in_memory().unwrap();
+    let client = client(&server);
+    let tariff: TariffCode = "E-1R-VAR-22-11-01-C".parse().unwrap();
+    let since = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    // The first mock answers once; later requests fall through to the second.
+    store.sync_tariff_charges(&client, &tariff, ChargeType::StandardUnitRates, since).await.unwrap();
+    let report = store
+        .sync_tariff_charges(&client, &tariff, ChargeType::StandardUnitRates, since)
+        .await
+        .unwrap();
+    assert_eq!(report.fetched, 2);
+    // Like consumption, `synced_to` is where the next sync starts.
+    assert_eq!(report.synced_to.unwrap().to_rfc3339(), "2024-04-01T00:00:00+00:00");
+
+    let charges = store.tariff_charges(&tariff, ChargeType::StandardUnitRates, None, None).unwrap();
+    assert_eq!(charges.len(), 2);
+    assert!(charges[0].valid_to.is_some());
+    assert!(charges[1].valid_to.is_none());
+
+    let from_may = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+    let current = store.tariff_charges(&tariff, ChargeType::StandardUnitRates, Some(from_may), None).unwrap();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0].value_exc_vat, 24.0);
+    assert!(store.tariff_charges(&tariff, ChargeType::StandingCharges, None, None).unwrap().is_empty());
+}
@@ -0,0 +1,158 @@
+use futures::{StreamExt, TryStreamExt};
+use octopust::{
+    models::{ListElectrictyConsumptionQuery, ListGridSupplyPointsQuery},
+    pagination::PaginationLimits,
+    Client, OctopustError,
+};
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const GSP_PATH: &str = "/industry/grid-supply-points/";
+
+/// Three pages of grid supply points: `_A`/`_B`, `_C`/`_D`, then `_E`.
+async fn mount_gsp_pages(server: &MockServer) {
+    let pages = [(None, vec!["_A", "_B"]), (Some("2"), vec!["_C", "_D"]), (Some("3"), vec!["_E"])];
+    for (i, (page, ids)) in pages.iter().enumerate() {
+        let next = (i + 1 < pages.len()).then(|| format!("{}{}?page={}", server.uri(), GSP_PATH, i + 2));
+        let results: Vec<_> = ids.iter().map(|id| serde_json::json!({ "group_id": id })).collect();
+        let mock = Mock::given(method("GET")).and(path(GSP_PATH));
+        let mock = match page {
+            Some(page) => mock.and(query_param("page", *page)),
+            None => mock.and(query_param_is_missing("page")),
+        };
+        mock.respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 5,
+            "next": next,
+            "previous": null,
+            "results": results
+        })))
+        .mount(server)
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn test_stream_follows_every_page() {
+    let server = MockServer::start().await;
+    mount_gsp_pages(&server).await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let ids: Vec<String> = client
+        .stream_industry_grid_supply_points(ListGridSupplyPointsQuery::default(), PaginationLimits::default())
+        .map_ok(|gsp| gsp.group_id)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(ids, ["_A", "_B", "_C", "_D", "_E"]);
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_stream_stops_at_limits() {
+    let server = MockServer::start().await;
+    mount_gsp_pages(&server).await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let by_items: Vec<_> = client
+        .stream_industry_grid_supply_points(ListGridSupplyPointsQuery::default(), PaginationLimits {
+            max_items: Some(3),
+            ..Default::default()
+        })
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(by_items.len(), 3);
+    // The third item is on page two, so page three is never requested.
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+
+    let by_pages: Vec<_> = client
+        .stream_industry_grid_supply_points(ListGridSupplyPointsQuery::default(), PaginationLimits {
+            max_pages: Some(2),
+            ..Default::default()
+        })
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(by_pages.len(), 4);
+}
+
+#[tokio::test]
+async fn test_stream_sends_query_on_first_request_only() {
+    let server = MockServer::start().await;
+    mount_gsp_pages(&server).await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let all: Vec<_> = client
+        .stream_industry_grid_supply_points(
+            ListGridSupplyPointsQuery { postcode: Some("SW1A 1AA"), ..Default::default() },
+            PaginationLimits::default(),
+        )
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 5);
+
+    let requests = server.received_requests().await.unwrap();
+    let postcodes: Vec<_> = requests
+        .iter()
+        .map(|r| r.url.query_pairs().find(|(k, _)| k == "postcode").map(|(_, v)| v.into_owned()))
+        .collect();
+    assert_eq!(postcodes, [Some("SW1A 1AA".to_string()), None, None]);
+}
+
+#[tokio::test]
+async fn test_stream_stops_after_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(GSP_PATH))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let results: Vec<_> = client
+        .stream_industry_grid_supply_points(ListGridSupplyPointsQuery::default(), PaginationLimits::default())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0], Err(OctopustError::NotFound(_))), "{:?}", results[0]);
+}
+
+#[tokio::test]
+async fn test_consumption_stream_needs_api_key() {
+    let server = MockServer::start().await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let results: Vec<_> = client
+        .stream_electricity_consumption(
+            ListElectrictyConsumptionQuery {
+                mpan: "2000000000000",
+                serial_number: "21L0000000",
+                ..Default::default()
+            },
+            PaginationLimits::default(),
+        )
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0], Err(OctopustError::MissingApiKey)), "{:?}", results[0]);
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn test_stream_grid_supply_points() {
+    let mock = octopust::testing::MockOctopus::start().await;
+    let supply_points: Vec<_> = mock
+        .client()
+        .stream_industry_grid_supply_points(
+            ListGridSupplyPointsQuery::default(),
+            PaginationLimits { max_items: Some(5), ..Default::default() },
+        )
+        .try_collect()
+        .await
+        .unwrap();
+    let ids: Vec<_> = supply_points.iter().map(|p| p.group_id.as_str()).collect();
+    assert_eq!(ids, ["_A", "_B", "_C", "_D", "_E"]);
+}
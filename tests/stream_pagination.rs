@@ -0,0 +1,90 @@
+use futures::TryStreamExt;
+use octopust::{models::ListUnitRatesQuery, pagination::PaginationLimits, Client};
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn charge(value: f64) -> serde_json::Value {
+    serde_json::json!({
+        "value_exc_vat": value,
+        "value_inc_vat": value * 1.05,
+        "valid_from": "2024-01-01T00:00:00Z",
+        "valid_to": null,
+        "payment_method": null
+    })
+}
+
+async fn mount_pages(server: &MockServer) {
+    let rates_path = "/products/AGILE-24-10-01/electricity-tariffs/E-1R-AGILE-24-10-01-C/standard-unit-rates/";
+    Mock::given(method("GET"))
+        .and(path(rates_path))
+        .and(query_param_is_missing("page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 3,
+            "next": format!("{}{}?page=2", server.uri(), rates_path),
+            "previous": null,
+            "results": [charge(1.0), charge(2.0)]
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(rates_path))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 3,
+            "next": null,
+            "previous": null,
+            "results": [charge(3.0)]
+        })))
+        .mount(server)
+        .await;
+}
+
+fn query() -> ListUnitRatesQuery<'static> {
+    ListUnitRatesQuery {
+        product_code: "AGILE-24-10-01",
+        tariff_code: "E-1R-AGILE-24-10-01-C",
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_stream_follows_next_links() {
+    let server = MockServer::start().await;
+    mount_pages(&server).await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let rates: Vec<_> = client
+        .stream_electricity_tariff_standard_unit_rates(query(), PaginationLimits::default())
+        .try_collect()
+        .await
+        .unwrap();
+    let values: Vec<f64> = rates.iter().map(|r| r.value_exc_vat).collect();
+    assert_eq!(values, vec![1.0, 2.0, 3.0]);
+}
+
+#[tokio::test]
+async fn test_stream_respects_limits() {
+    let server = MockServer::start().await;
+    mount_pages(&server).await;
+    let client = Client::builder().base_url(server.uri()).build().unwrap();
+
+    let by_items: Vec<_> = client
+        .stream_electricity_tariff_standard_unit_rates(query(), PaginationLimits {
+            max_items: Some(1),
+            ..Default::default()
+        })
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(by_items.len(), 1);
+
+    let by_pages: Vec<_> = client
+        .stream_electricity_tariff_standard_unit_rates(query(), PaginationLimits {
+            max_pages: Some(1),
+            ..Default::default()
+        })
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(by_pages.len(), 2);
+}
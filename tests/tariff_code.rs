@@ -0,0 +1,118 @@
+use octopust::models::{ListUnitRatesQuery, ProductDetail};
+use octopust::tariff_code::{Fuel, RegisterType, TariffCode};
+use octopust::Region;
+
+fn product() -> ProductDetail {
+    let tariff = |code: &str| {
+        serde_json::json!({
+            "direct_debit_monthly": {
+                "code": code,
+                "standard_unit_rate_exc_vat": 20.0,
+                "standard_unit_rate_inc_vat": 21.0
+            }
+        })
+    };
+    serde_json::from_value(serde_json::json!({
+        "tariffs_active_at": "2024-06-01T00:00:00Z",
+        "code": "VAR-22-11-01",
+        "full_name": "Flexible Octopus November 2022 v1",
+        "display_name": "Flexible Octopus",
+        "description": null,
+        "is_variable": true,
+        "is_green": false,
+        "is_tracker": false,
+        "is_prepay": false,
+        "is_business": false,
+        "is_restricted": false,
+        "term": null,
+        "available_from": "2022-11-01T00:00:00Z",
+        "available_to": null,
+        "brand": "OCTOPUS_ENERGY",
+        "links": [],
+        "single_register_electricity_tariffs": { "_J": tariff("E-1R-VAR-22-11-01-J") },
+        "dual_register_electricity_tariffs": { "_J": tariff("E-2R-VAR-22-11-01-J") },
+        "single_register_gas_tariffs": { "_A": tariff("G-1R-VAR-22-11-01-A") },
+        "sample_quotes": null,
+        "sample_consumption": null
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_parse_and_display_round_trip() {
+    for code in ["E-1R-VAR-22-11-01-J", "E-2R-AGILE-24-10-01-C", "G-1R-SILVER-FLEX-BB-23-02-08-P"] {
+        let parsed: TariffCode = code.parse().unwrap();
+        assert_eq!(parsed.to_string(), code);
+    }
+
+    let code: TariffCode = "E-1R-OUTGOING-FIX-12M-19-05-13-A".parse().unwrap();
+    assert_eq!(code.fuel(), Fuel::Electricity);
+    assert_eq!(code.register_type(), RegisterType::Single);
+    assert_eq!(code.product_code(), "OUTGOING-FIX-12M-19-05-13");
+    assert_eq!(code.region(), 'A');
+    assert_eq!(code.dno_region(), Region::EasternEngland);
+}
+
+#[test]
+fn test_rejects_malformed_codes() {
+    for code in [
+        "",
+        "VAR-22-11-01",
+        "X-1R-VAR-22-11-01-J",
+        "E-3R-VAR-22-11-01-J",
+        "G-2R-VAR-22-11-01-J",
+        "E-1R-VAR-22-11-01-I",
+        "E-1R-VAR-22-11-01-JJ",
+        "E-1R-VAR-22-11-01-j",
+        "E-1R-J",
+        "E-1R--J",
+        "E-1R-VAR 22-J",
+    ] {
+        let err = code.parse::<TariffCode>().unwrap_err();
+        assert!(matches!(err, octopust::OctopustError::InvalidInput(_)), "{code}: {err}");
+    }
+}
+
+#[test]
+fn test_new_builds_the_code() {
+    let code = TariffCode::new(Fuel::Gas, RegisterType::Single, "VAR-22-11-01", Region::EastMidlands).unwrap();
+    assert_eq!(code.as_str(), "G-1R-VAR-22-11-01-B");
+    assert!(TariffCode::new(Fuel::Electricity, RegisterType::Single, "VAR 22", Region::London).is_err());
+
+    // Region letters are accepted as well.
+    let code = TariffCode::new(Fuel::Gas, RegisterType::Single, "VAR-22-11-01", 'B').unwrap();
+    assert_eq!(code.dno_region(), Region::EastMidlands);
+    assert!(TariffCode::new(Fuel::Electricity, RegisterType::Single, "VAR-22-11-01", 'Z').is_err());
+}
+
+#[test]
+fn test_for_product_uses_listed_tariffs() {
+    let product = product();
+
+    let dual = TariffCode::for_product(&product, Fuel::Electricity, RegisterType::Dual, Region::SouthEasternEngland).unwrap();
+    assert_eq!(dual.as_str(), "E-2R-VAR-22-11-01-J");
+    let gas = TariffCode::for_product(&product, Fuel::Gas, RegisterType::Single, Region::EasternEngland).unwrap();
+    assert_eq!(gas.as_str(), "G-1R-VAR-22-11-01-A");
+
+    assert!(TariffCode::for_product(&product, Fuel::Electricity, RegisterType::Single, Region::EasternEngland).is_err());
+    assert!(TariffCode::for_product(&product, Fuel::Gas, RegisterType::Dual, Region::EasternEngland).is_err());
+
+    let dual = TariffCode::for_product(&product, Fuel::Electricity, RegisterType::Dual, 'J').unwrap();
+    assert_eq!(dual.as_str(), "E-2R-VAR-22-11-01-J");
+    assert!(TariffCode::for_product(&product, Fuel::Electricity, RegisterType::Single, 'A').is_err());
+}
+
+#[test]
+fn test_unit_rates_query_for_tariff() {
+    let code: TariffCode = "E-1R-VAR-22-11-01-J".parse().unwrap();
+    let query = ListUnitRatesQuery::for_tariff(&code);
+    assert_eq!(query.product_code, "VAR-22-11-01");
+    assert_eq!(query.tariff_code, "E-1R-VAR-22-11-01-J");
+}
+
+#[test]
+fn test_serde_as_string() {
+    let code: TariffCode = serde_json::from_str("\"E-2R-VAR-22-11-01-J\"").unwrap();
+    assert_eq!(serde_json::to_string(&code).unwrap(), "\"E-2R-VAR-22-11-01-J\"");
+    assert!(serde_json::from_str::<TariffCode>("\"nonsense\"").is_err());
+}
@@ -0,0 +1,213 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::StreamExt;
+use octopust::graphql::telemetry::{TelemetryGrouping, TelemetryPollOptions, TelemetryQuery};
+use octopust::retry::RetryPolicy;
+use octopust::{Client, OctopustError};
+use wiremock::matchers::{body_string_contains, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_login(server: &MockServer) {
+    let exp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 3600;
+    Mock::given(method("POST"))
+        .and(body_string_contains("obtainKrakenToken"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": { "obtainKrakenToken": { "token": "jwt-token", "payload": { "exp": exp } } }
+        })))
+        .mount(server)
+        .await;
+}
+
+fn client_for(server: &MockServer) -> Client {
+    Client::builder()
+        .api_key("sk_test")
+        .graphql_url(format!("{}/graphql/", server.uri()))
+        .build()
+        .unwrap()
+}
+
+fn reading(read_at: &str, demand: f64) -> serde_json::Value {
+    serde_json::json!({
+        "readAt": read_at,
+        "consumption": "1523400.0",
+        "demand": demand.to_string(),
+        "consumptionDelta": 12.5,
+        "costDelta": "0.31",
+        "costDeltaWithTax": null
+    })
+}
+
+fn telemetry(readings: Vec<serde_json::Value>) -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .set_body_json(serde_json::json!({ "data": { "smartMeterTelemetry": readings } }))
+}
+
+#[tokio::test]
+async fn test_smart_meter_telemetry() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("smartMeterTelemetry"))
+        .and(body_string_contains("\"grouping\":\"FIVE_MINUTES\""))
+        .respond_with(telemetry(vec![reading("2024-06-01T12:00:00+00:00", 412.0)]))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server);
+    let readings = client
+        .kraken()
+        .smart_meter_telemetry(TelemetryQuery {
+            device_id: "00-11-22-33-44-55-66-77",
+            grouping: Some(TelemetryGrouping::FiveMinutes),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(readings.len(), 1);
+    assert_eq!(readings[0].consumption, Some(1_523_400.0));
+    assert_eq!(readings[0].demand, Some(412.0));
+    assert_eq!(readings[0].consumption_delta, Some(12.5));
+    assert_eq!(readings[0].cost_delta, Some(0.31));
+    assert_eq!(readings[0].cost_delta_with_tax, None);
+}
+
+#[tokio::test]
+async fn test_poll_telemetry_skips_seen_readings() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    let responses = [
+        vec![reading("2024-06-01T12:00:10+00:00", 2.0), reading("2024-06-01T12:00:00+00:00", 1.0)],
+        vec![reading("2024-06-01T12:00:10+00:00", 2.0)],
+        vec![],
+        vec![reading("2024-06-01T12:00:10+00:00", 2.0), reading("2024-06-01T12:00:20+00:00", 3.0)],
+    ];
+    for readings in responses {
+        Mock::given(method("POST"))
+            .and(body_string_contains("smartMeterTelemetry"))
+            .respond_with(telemetry(readings))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server);
+    let options = TelemetryPollOptions {
+        interval: Duration::from_millis(5),
+        max_interval: Duration::from_millis(20),
+        grouping: None,
+    };
+    let demands: Vec<Option<f64>> = client
+        .kraken()
+        .poll_telemetry("00-11-22-33-44-55-66-77", options)
+        .take(3)
+        .map(|r| r.unwrap().demand)
+        .collect()
+        .await;
+
+    assert_eq!(demands, vec![Some(1.0), Some(2.0), Some(3.0)]);
+}
+
+#[tokio::test]
+async fn test_poll_telemetry_asks_for_readings_since_the_last_one() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("smartMeterTelemetry"))
+        .and(body_string_contains("\"start\":null"))
+        .respond_with(telemetry(vec![reading("2024-06-01T12:00:10+00:00", 1.0)]))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // Two readings arrived while the client was backing off.
+    Mock::given(method("POST"))
+        .and(body_string_contains("smartMeterTelemetry"))
+        .and(body_string_contains("\"start\":\"2024-06-01T12:00:10"))
+        .respond_with(telemetry(vec![
+            reading("2024-06-01T12:00:10+00:00", 1.0),
+            reading("2024-06-01T12:00:20+00:00", 2.0),
+            reading("2024-06-01T12:00:30+00:00", 3.0),
+        ]))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server);
+    let options = TelemetryPollOptions {
+        interval: Duration::from_millis(5),
+        ..Default::default()
+    };
+    let demands: Vec<Option<f64>> = client
+        .kraken()
+        .poll_telemetry("device", options)
+        .take(3)
+        .map(|r| r.unwrap().demand)
+        .collect()
+        .await;
+
+    assert_eq!(demands, vec![Some(1.0), Some(2.0), Some(3.0)]);
+}
+
+#[tokio::test]
+async fn test_poll_telemetry_yields_errors_and_continues() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("smartMeterTelemetry"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("smartMeterTelemetry"))
+        .respond_with(telemetry(vec![reading("2024-06-01T12:00:00+00:00", 1.0)]))
+        .mount(&server)
+        .await;
+
+    let client = Client::builder()
+        .api_key("sk_test")
+        .graphql_url(format!("{}/graphql/", server.uri()))
+        .retry_policy(RetryPolicy::disabled())
+        .build()
+        .unwrap();
+    let options = TelemetryPollOptions {
+        interval: Duration::from_millis(5),
+        ..Default::default()
+    };
+    let results: Vec<_> = client.kraken().poll_telemetry("device", options).take(2).collect().await;
+
+    assert!(matches!(results[0], Err(OctopustError::Api(_))), "{:?}", results[0]);
+    assert_eq!(results[1].as_ref().unwrap().demand, Some(1.0));
+}
+
+#[tokio::test]
+async fn test_poll_telemetry_stops_on_permanent_error() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("smartMeterTelemetry"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "errors": [{ "message": "Device not reachable", "extensions": { "errorCode": "KT-CT-4301" } }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server);
+    let options = TelemetryPollOptions {
+        interval: Duration::from_millis(5),
+        ..Default::default()
+    };
+    let results: Vec<_> = client.kraken().poll_telemetry("device", options).collect().await;
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0], Err(OctopustError::Graphql(_))), "{:?}", results[0]);
+
+    // Without an API key no request is made at all.
+    let anonymous = Client::builder()
+        .graphql_url(format!("{}/graphql/", server.uri()))
+        .build()
+        .unwrap();
+    let results: Vec<_> = anonymous.kraken().poll_telemetry("device", options).collect().await;
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0], Err(OctopustError::MissingApiKey)), "{:?}", results[0]);
+}
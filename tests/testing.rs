@@ -0,0 +1,328 @@
+#![cfg(feature = "testing")]
+
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use octopust::models::{
+    GroupBy, ListElectrictyConsumptionQuery, ListGasConsumptionQuery, ListGridSupplyPointsQuery, ListUnitRatesQuery,
+    Order, ProductQuery, QueryTimestamp, RetrieveProductQuery,
+};
+use octopust::pagination::PaginationLimits;
+use octopust::retry::RetryPolicy;
+use octopust::tariff_code::{Fuel, RegisterType};
+use octopust::testing::{fixtures, Fault, MockOctopus};
+use octopust::{Client, OctopustError, Region, TariffCode};
+
+#[cfg(feature = "chrono")]
+fn ts(s: &str) -> QueryTimestamp<'_> {
+    s.parse().unwrap()
+}
+
+#[cfg(not(feature = "chrono"))]
+fn ts(s: &str) -> QueryTimestamp<'_> {
+    s
+}
+
+fn rates_query(product_code: &'static str, tariff_code: &'static str) -> ListUnitRatesQuery<'static> {
+    ListUnitRatesQuery { product_code, tariff_code, ..Default::default() }
+}
+
+fn electricity_query() -> ListElectrictyConsumptionQuery<'static> {
+    ListElectrictyConsumptionQuery {
+        mpan: fixtures::ELECTRICITY_MPAN,
+        serial_number: fixtures::ELECTRICITY_SERIAL,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_products_and_tariff_codes() {
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+
+    let products = client.list_products(ProductQuery::default()).await.unwrap();
+    assert_eq!(products.len(), 3);
+
+    let fixed = client
+        .list_products(ProductQuery { is_variable: Some(&false), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(fixed.len(), 1);
+    assert_eq!(fixed[0].code, fixtures::FIXED_PRODUCT);
+
+    let detail = client
+        .retrieve_product(RetrieveProductQuery { product_code: fixtures::FLEXIBLE_PRODUCT, ..Default::default() })
+        .await
+        .unwrap();
+    let code = TariffCode::for_product(&detail, Fuel::Electricity, RegisterType::Dual, Region::London).unwrap();
+    assert_eq!(code.as_str(), "E-2R-VAR-22-11-01-C");
+    let code = TariffCode::for_product(&detail, Fuel::Gas, RegisterType::Single, Region::NorthernScotland).unwrap();
+    assert_eq!(code.as_str(), "G-1R-VAR-22-11-01-P");
+
+    let err = client
+        .retrieve_product(RetrieveProductQuery { product_code: "NOPE-01", ..Default::default() })
+        .await
+        .unwrap_err();
+    assert!(err.is_not_found(), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_rates_are_paginated_newest_first() {
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+
+    let rates: Vec<_> = client
+        .stream_electricity_tariff_standard_unit_rates(
+            rates_query(fixtures::AGILE_PRODUCT, "E-1R-AGILE-24-10-01-C"),
+            PaginationLimits::default(),
+        )
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(rates.len(), 336);
+    assert!(rates.windows(2).all(|w| w[0].valid_from > w[1].valid_from));
+
+    let pages: Vec<_> = mock.requests().iter().map(|r| r.param("page").map(str::to_string)).collect();
+    assert_eq!(pages, [None, Some("2".into()), Some("3".into()), Some("4".into())]);
+}
+
+#[tokio::test]
+async fn test_every_charge_type_parses() {
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+    let product = fixtures::FLEXIBLE_PRODUCT;
+
+    let standard = client
+        .list_electricity_tariff_standard_unit_rates(rates_query(product, "E-1R-VAR-22-11-01-A"))
+        .await
+        .unwrap();
+    assert_eq!(standard.count, 4);
+    let day = client
+        .list_electricity_tariff_day_unit_rates(rates_query(product, "E-2R-VAR-22-11-01-A"))
+        .await
+        .unwrap();
+    assert_eq!(day.count, 2);
+    let night = client
+        .list_electricity_tariff_night_unit_rates(rates_query(product, "E-2R-VAR-22-11-01-A"))
+        .await
+        .unwrap();
+    assert!(night.results[0].value_exc_vat < day.results[0].value_exc_vat);
+    let standing = client
+        .list_electricity_tariff_standing_charges(rates_query(product, "E-1R-VAR-22-11-01-A"))
+        .await
+        .unwrap();
+    assert_eq!(standing.count, 2);
+    let gas = client
+        .list_gas_tariff_standard_unit_rates(rates_query(product, "G-1R-VAR-22-11-01-A"))
+        .await
+        .unwrap();
+    assert_eq!(gas.count, 4);
+    let gas_standing = client
+        .list_gas_tariff_standing_charges(rates_query(product, "G-1R-VAR-22-11-01-A"))
+        .await
+        .unwrap();
+    assert!(gas_standing.results[0].valid_to.is_none());
+
+    // A tariff is only served under its own product.
+    let err = client
+        .list_electricity_tariff_standard_unit_rates(rates_query(fixtures::AGILE_PRODUCT, "E-1R-VAR-22-11-01-A"))
+        .await
+        .unwrap_err();
+    assert!(err.is_not_found(), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_rates_filtered_by_period() {
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+
+    let rates = client
+        .list_electricity_tariff_standard_unit_rates(ListUnitRatesQuery {
+            period_from: Some(ts("2024-01-05T00:00:00Z")),
+            ..rates_query(fixtures::FLEXIBLE_PRODUCT, "E-1R-VAR-22-11-01-C")
+        })
+        .await
+        .unwrap();
+    assert_eq!(rates.count, 2);
+    assert!(rates.results.iter().all(|r| r.valid_to.is_none()));
+
+    let rates = client
+        .list_electricity_tariff_standard_unit_rates(ListUnitRatesQuery {
+            period_from: Some(ts("2024-01-01T00:00:00Z")),
+            period_to: Some(ts("2024-01-01T02:00:00Z")),
+            ..rates_query(fixtures::AGILE_PRODUCT, "E-1R-AGILE-24-10-01-C")
+        })
+        .await
+        .unwrap();
+    assert_eq!(rates.count, 4);
+}
+
+#[tokio::test]
+async fn test_invalid_timestamps_are_rejected() {
+    let mock = MockOctopus::start().await;
+    let url = format!(
+        "{}/products/{}/electricity-tariffs/E-1R-AGILE-24-10-01-C/standard-unit-rates/",
+        mock.uri(),
+        fixtures::AGILE_PRODUCT
+    );
+    let http = reqwest::Client::new();
+
+    for valid in ["2024-02-29T00:00:00Z", "2024-01-01T00:00+01:00", "2024-01-01T00:00:00.5-05:00"] {
+        let resp = http.get(&url).query(&[("period_from", valid)]).send().await.unwrap();
+        assert_eq!(resp.status(), 200, "{valid}");
+    }
+    for invalid in [
+        "2023-02-29T00:00:00Z",
+        "2024-04-31T00:00:00Z",
+        "2024-13-01T00:00:00Z",
+        "2024-01-00T00:00:00Z",
+        "2024-01-01T24:00:00Z",
+        "2024-01-01T12:60:00Z",
+        "2024-01-01T12:00:00+25:00",
+        "2024-1-1T00:00:00Z",
+        "2024-01-01T00:00:00",
+        "2024-01-01T00:00:00:00Z",
+        "2024-01-01",
+    ] {
+        let resp = http.get(&url).query(&[("period_from", invalid)]).send().await.unwrap();
+        assert_eq!(resp.status(), 400, "{invalid}");
+    }
+}
+
+#[tokio::test]
+async fn test_consumption() {
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+
+    let day = client
+        .list_electricity_consumption(ListElectrictyConsumptionQuery {
+            period_from: Some(ts("2024-01-02T00:00:00Z")),
+            period_to: Some(ts("2024-01-03T00:00:00Z")),
+            order_by: Some(Order::PeriodAsc),
+            page_size: Some(1000),
+            ..electricity_query()
+        })
+        .await
+        .unwrap();
+    assert_eq!(day.count, 48);
+    assert!(day.next.is_none());
+    assert!(day.results.windows(2).all(|w| w[0].interval_start < w[1].interval_start));
+
+    let gas: Vec<_> = client
+        .stream_gas_consumption(
+            ListGasConsumptionQuery {
+                mprn: fixtures::GAS_MPRN,
+                serial_number: fixtures::GAS_SERIAL,
+                ..Default::default()
+            },
+            PaginationLimits::default(),
+        )
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(gas.len(), 336);
+
+    let err = client
+        .list_electricity_consumption(ListElectrictyConsumptionQuery {
+            group_by: Some(GroupBy::Day),
+            ..electricity_query()
+        })
+        .await
+        .unwrap_err();
+    assert!(err.api_error().unwrap().field_errors.contains_key("group_by"), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_consumption_checks_api_key() {
+    let mock = MockOctopus::start().await;
+    let client = Client::builder().base_url(mock.uri()).api_key("sk_wrong").build().unwrap();
+
+    let err = client.list_electricity_consumption(electricity_query()).await.unwrap_err();
+    assert!(matches!(err, OctopustError::Auth(_)), "{err:?}");
+    assert_eq!(mock.requests()[0].api_key.as_deref(), Some("sk_wrong"));
+}
+
+#[tokio::test]
+async fn test_meter_points_and_accounts() {
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+
+    let mpan = client.get_electricity_meter_point(fixtures::ELECTRICITY_MPAN).await.unwrap();
+    assert_eq!(mpan.gsp, Region::London.gsp_group_id());
+
+    let account = client.get_account(fixtures::ACCOUNT_NUMBER).await.unwrap();
+    let electricity: Vec<_> = account.electricity_meter_points().collect();
+    assert_eq!(electricity[0].mpan, fixtures::ELECTRICITY_MPAN);
+    assert_eq!(electricity[0].meters[0].serial_number, fixtures::ELECTRICITY_SERIAL);
+    let current = electricity[0].agreements.iter().find(|a| a.valid_to.is_none()).unwrap();
+    assert_eq!(current.tariff_code, "E-1R-AGILE-24-10-01-C");
+    let gas: Vec<_> = account.gas_meter_points().collect();
+    assert_eq!(gas[0].meters[0].serial_number, fixtures::GAS_SERIAL);
+
+    let err = client.get_account("A-00000000").await.unwrap_err();
+    assert!(err.is_not_found(), "{err:?}");
+
+    let wrong_key = Client::builder().base_url(mock.uri()).api_key("sk_wrong").build().unwrap();
+    let err = wrong_key.get_account(fixtures::ACCOUNT_NUMBER).await.unwrap_err();
+    assert!(matches!(err, OctopustError::Auth(_)), "{err:?}");
+    let err = wrong_key.get_electricity_meter_point(fixtures::ELECTRICITY_MPAN).await.unwrap_err();
+    assert!(matches!(err, OctopustError::Auth(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_grid_supply_points_and_regions() {
+    let mock = MockOctopus::start().await;
+    let client = mock.client();
+
+    let all = client
+        .list_industry_grid_supply_points(ListGridSupplyPointsQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(all.count, 14);
+    assert_eq!(client.region_for_postcode("sw1a1aa").await.unwrap(), Region::London);
+    assert!(client.region_for_postcode("ZZ99 9ZZ").await.is_err());
+}
+
+#[tokio::test]
+async fn test_faults_are_retried() {
+    let mock = MockOctopus::start().await;
+    mock.inject(Fault::status(503).on_path("/products/").times(2));
+    let client = mock
+        .client_builder()
+        .retry_policy(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            jitter: false,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    let products = client.list_products(ProductQuery::default()).await.unwrap();
+    assert_eq!(products.len(), 3);
+    assert_eq!(mock.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn test_injected_errors() {
+    let mock = MockOctopus::start().await;
+    let client = mock.client_builder().retry_policy(RetryPolicy::disabled()).build().unwrap();
+
+    mock.inject(Fault::rate_limited(30));
+    let err = client.list_products(ProductQuery::default()).await.unwrap_err();
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(30)));
+
+    mock.clear_faults();
+    mock.inject(Fault::malformed_body());
+    let err = client.list_products(ProductQuery::default()).await.unwrap_err();
+    assert!(err.is_decode(), "{err:?}");
+
+    mock.clear_faults();
+    let err = client
+        .list_electricity_tariff_standard_unit_rates(ListUnitRatesQuery {
+            page: Some(10),
+            ..rates_query(fixtures::AGILE_PRODUCT, "E-1R-AGILE-24-10-01-C")
+        })
+        .await
+        .unwrap_err();
+    assert!(err.is_not_found(), "{err:?}");
+}
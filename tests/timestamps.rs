@@ -0,0 +1,52 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{FixedOffset, TimeZone, Utc};
+use octopust::{models::ListElectrictyConsumptionQuery, Client};
+use wiremock::matchers::{method, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_consumption_timestamps_keep_offset() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("period_from", "2024-03-31T00:00:00Z"))
+        .and(query_param("period_to", "2024-04-01T00:00:00Z"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": 1,
+            "next": null,
+            "previous": null,
+            "results": [{
+                "consumption": 0.25,
+                "interval_start": "2024-03-31T02:00:00+01:00",
+                "interval_end": "2024-03-31T02:30:00+01:00"
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = Client::builder()
+        .api_key("sk_test")
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+
+    let consumption = client.list_electricity_consumption(ListElectrictyConsumptionQuery {
+        mpan: "1000000000000",
+        serial_number: "00A0000000",
+        period_from: Some(Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap()),
+        period_to: Some(Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap()),
+        ..Default::default()
+    }).await.unwrap();
+
+    let reading = &consumption.results[0];
+    let bst = FixedOffset::east_opt(3600).unwrap();
+    assert_eq!(reading.interval_start.offset(), &bst);
+    assert_eq!(
+        reading.interval_start,
+        bst.with_ymd_and_hms(2024, 3, 31, 2, 0, 0).unwrap()
+    );
+    assert_eq!(
+        reading.interval_start.with_timezone(&Utc),
+        Utc.with_ymd_and_hms(2024, 3, 31, 1, 0, 0).unwrap()
+    );
+}